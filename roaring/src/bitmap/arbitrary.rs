@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod test {
     use crate::bitmap::container::Container;
-    use crate::bitmap::store::{ArrayStore, BitmapStore, Store};
+    use crate::bitmap::store::{ArrayStore, BitmapStore, IntervalStore, Store};
     use crate::RoaringBitmap;
     use core::fmt::{Debug, Formatter};
     use proptest::bits::{BitSetLike, BitSetStrategy, SampledBitSetStrategy};
@@ -155,11 +155,42 @@ mod test {
         }
     }
 
+    impl Debug for IntervalStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+            if self.run_amount() < 16 {
+                let runs: Vec<_> = self.as_slice().iter().map(|iv| iv.range()).collect();
+                write!(f, "IntervalStore<{runs:?}>")
+            } else {
+                write!(
+                    f,
+                    "IntervalStore<{:?} runs, {:?} values between {:?} and {:?}>",
+                    self.run_amount(),
+                    self.len(),
+                    self.min().unwrap(),
+                    self.max().unwrap()
+                )
+            }
+        }
+    }
+
+    impl IntervalStore {
+        pub fn arbitrary() -> impl Strategy<Value = IntervalStore> {
+            vec((0..=u16::MAX, 0..=1024_u16), 1..=64).prop_map(|ranges| {
+                let mut store = IntervalStore::new();
+                for (start, len) in ranges {
+                    store.insert_range(start..=start.saturating_add(len));
+                }
+                store
+            })
+        }
+    }
+
     impl Debug for Store {
         fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
             match self {
                 Store::Array(a) => write!(f, "Store({a:?})"),
                 Store::Bitmap(b) => write!(f, "Store({b:?})"),
+                Store::Run(r) => write!(f, "Store({r:?})"),
             }
         }
     }
@@ -170,6 +201,7 @@ mod test {
                 ArrayStore::sampled(1..=4096, ..=u16::MAX as usize).prop_map(Store::Array),
                 BitmapStore::sampled(4097..u16::MAX as usize, ..=u16::MAX as usize)
                     .prop_map(Store::Bitmap),
+                IntervalStore::arbitrary().prop_map(Store::Run),
             ]
         }
     }
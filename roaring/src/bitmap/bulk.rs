@@ -0,0 +1,140 @@
+use crate::RoaringBitmap;
+
+use super::container::Container;
+use super::util;
+
+/// A reusable context for looking up many values in a bitmap, see
+/// [`RoaringBitmap::contains_bulk`].
+///
+/// It remembers the container of the last probed value, so probes that land
+/// in the same 16-bit chunk skip the container lookup entirely.
+///
+/// A context can be shared between bitmaps, the cached position is checked
+/// against the bitmap before being used.
+#[derive(Clone, Debug, Default)]
+pub struct BulkContext {
+    // The key of the last probed container and its index,
+    // or the index where it would be inserted if it is absent.
+    last: Option<(u16, usize)>,
+}
+
+impl BulkContext {
+    /// Creates an empty context.
+    pub fn new() -> BulkContext {
+        BulkContext::default()
+    }
+
+    fn find(&mut self, containers: &[Container], key: u16) -> Result<usize, usize> {
+        let start = match self.last {
+            Some((last_key, index)) if last_key == key => match containers.get(index) {
+                Some(container) if container.key == key => return Ok(index),
+                next => {
+                    let after_previous = index
+                        .checked_sub(1)
+                        .map_or(true, |i| containers.get(i).map_or(false, |c| c.key < key));
+                    if after_previous && next.map_or(index == containers.len(), |c| c.key > key) {
+                        return Err(index);
+                    }
+                    0
+                }
+            },
+            // The values are expected to be mostly sorted, start looking after the last one
+            // if every container before it is known to be smaller than the key.
+            Some((last_key, index)) if last_key < key && index <= containers.len() => {
+                match index.checked_sub(1) {
+                    Some(i) if containers[i].key >= key => 0,
+                    _ => index,
+                }
+            }
+            _ => 0,
+        };
+        let found = match containers[start..].binary_search_by_key(&key, |c| c.key) {
+            Ok(index) => Ok(start + index),
+            Err(index) => Err(start + index),
+        };
+        self.last = Some((key, found.unwrap_or_else(|index| index)));
+        found
+    }
+}
+
+impl RoaringBitmap {
+    /// Returns `true` if this set contains the specified integer, reusing the container
+    /// found by the previous lookups made with the same context.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::bitmap::BulkContext;
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(100_000..100_010).collect();
+    /// let mut context = BulkContext::new();
+    ///
+    /// assert!(rb.contains_bulk(&mut context, 1));
+    /// assert!(!rb.contains_bulk(&mut context, 10));
+    /// assert!(rb.contains_bulk(&mut context, 100_005));
+    /// ```
+    #[inline]
+    pub fn contains_bulk(&self, context: &mut BulkContext, value: u32) -> bool {
+        let (key, index) = util::split(value);
+        match context.find(&self.containers, key) {
+            Ok(loc) => self.containers[loc].contains(index),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns an iterator telling whether each of the values is contained in this set.
+    ///
+    /// This is expected to be faster than calling [`RoaringBitmap::contains`] on each value,
+    /// especially when the values are mostly sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).collect();
+    /// let found: Vec<bool> = rb.contains_many(&[1, 5, 10, 3]).collect();
+    ///
+    /// assert_eq!(found, vec![true, true, false, true]);
+    /// ```
+    pub fn contains_many<'a>(&'a self, values: &'a [u32]) -> impl Iterator<Item = bool> + 'a {
+        let mut context = BulkContext::new();
+        values.iter().map(move |&value| self.contains_bulk(&mut context, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BulkContext;
+    use crate::RoaringBitmap;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn contains_many_eq_contains(
+            bitmap in RoaringBitmap::arbitrary(),
+            mut values in vec(0u32..0x12_0000, ..=1000),
+            sorted in any::<bool>(),
+        ) {
+            if sorted {
+                values.sort_unstable();
+            }
+            prop_assert!(bitmap.contains_many(&values).eq(values.iter().map(|&v| bitmap.contains(v))));
+        }
+
+        #[test]
+        fn context_shared_between_bitmaps(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary(),
+            values in vec(0u32..0x12_0000, ..=1000),
+        ) {
+            let mut context = BulkContext::new();
+            for (i, &value) in values.iter().enumerate() {
+                let bitmap = if i % 3 == 0 { &a } else { &b };
+                prop_assert_eq!(bitmap.contains_bulk(&mut context, value), bitmap.contains(value));
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use core::borrow::Borrow;
 use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::iter::Peekable;
+use core::ops::RangeInclusive;
 
 use super::container::Container;
 use crate::RoaringBitmap;
@@ -96,6 +98,64 @@ impl RoaringBitmap {
     }
 }
 
+impl Eq for RoaringBitmap {}
+
+// `Ord` is not implemented as its `min` and `max` methods would shadow
+// the inherent ones when called on an owned bitmap.
+impl PartialOrd for RoaringBitmap {
+    /// Compares the sorted values of the sets lexicographically,
+    /// run by run rather than value by value.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(cmp_ranges(self.ranges(), other.ranges()))
+    }
+}
+
+impl Hash for RoaringBitmap {
+    /// Hashes the values of the set, equal sets have the same hash
+    /// whatever the stores they are using.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.containers.len());
+        for container in &self.containers {
+            container.key.hash(state);
+            container.len().hash(state);
+            for range in container.ranges() {
+                range.start().hash(state);
+                range.end().hash(state);
+            }
+        }
+    }
+}
+
+/// Lexicographically compares the values of two sets given as their maximal runs.
+pub(crate) fn cmp_ranges<T, I, J>(mut lhs: I, mut rhs: J) -> Ordering
+where
+    T: Ord,
+    I: Iterator<Item = RangeInclusive<T>>,
+    J: Iterator<Item = RangeInclusive<T>>,
+{
+    loop {
+        let (l, r) = match (lhs.next(), rhs.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(l), Some(r)) => (l, r),
+        };
+        match l.start().cmp(r.start()) {
+            Ordering::Equal => (),
+            ordering => return ordering,
+        }
+        // The shortest run is followed by a gap, unless its set stops there
+        // and is a prefix of the other one.
+        match l.end().cmp(r.end()) {
+            Ordering::Equal => (),
+            Ordering::Less if lhs.next().is_some() => return Ordering::Greater,
+            Ordering::Less => return Ordering::Less,
+            Ordering::Greater if rhs.next().is_some() => return Ordering::Less,
+            Ordering::Greater => return Ordering::Greater,
+        }
+    }
+}
+
 /// An helping Iterator over pairs of containers.
 ///
 /// Returns the smallest container according to its key
@@ -150,3 +210,79 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringBitmap;
+    use core::hash::{Hash, Hasher};
+    use proptest::prelude::*;
+
+    #[cfg(not(feature = "std"))]
+    use alloc::vec::Vec;
+
+    /// Keeps every byte written to it, equal hashes mean equal inputs.
+    #[derive(Default)]
+    struct BytesHasher(Vec<u8>);
+
+    impl Hasher for BytesHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes);
+        }
+    }
+
+    fn hash(bitmap: &RoaringBitmap) -> Vec<u8> {
+        let mut hasher = BytesHasher::default();
+        bitmap.hash(&mut hasher);
+        hasher.0
+    }
+
+    proptest! {
+        #[test]
+        fn hash_ignores_stores(bitmap in RoaringBitmap::arbitrary()) {
+            let mut optimized = bitmap.clone();
+            optimized.optimize();
+            let rebuilt: RoaringBitmap = bitmap.iter().collect();
+
+            prop_assert_eq!(hash(&optimized), hash(&bitmap));
+            prop_assert_eq!(hash(&rebuilt), hash(&bitmap));
+        }
+
+        #[test]
+        fn hash_eq_iff_bitmaps_eq(a in RoaringBitmap::arbitrary(), b in RoaringBitmap::arbitrary()) {
+            prop_assert_eq!(hash(&a) == hash(&b), a == b);
+        }
+
+        #[test]
+        fn partial_cmp_eq_lexicographic_cmp(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary(),
+            shared in 0u64..=1024,
+        ) {
+            // Make the sets share a prefix so the comparison goes past the first values
+            let mut b = b;
+            b.remove_range(..a.select(shared as u32).unwrap_or(u32::MAX));
+            b |= a.iter().take(shared as usize).collect::<RoaringBitmap>();
+
+            prop_assert_eq!(a.partial_cmp(&b), a.iter().partial_cmp(b.iter()));
+            prop_assert_eq!(b.partial_cmp(&a), b.iter().partial_cmp(a.iter()));
+            prop_assert_eq!(a.partial_cmp(&a.clone()), Some(core::cmp::Ordering::Equal));
+        }
+    }
+
+    #[test]
+    fn cmp_prefix() {
+        let a = RoaringBitmap::from([1, 2, 3]);
+        let b = RoaringBitmap::from([1, 2, 3, 4]);
+        let c = RoaringBitmap::from([1, 2, 3, 5]);
+        let d = RoaringBitmap::from([1, 2, 70_000]);
+
+        assert!(a < b);
+        assert!(b < c);
+        assert!(c < d);
+        assert!(RoaringBitmap::new() < a);
+    }
+}
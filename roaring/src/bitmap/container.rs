@@ -3,7 +3,7 @@ use core::ops::{
     BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, RangeInclusive, Sub, SubAssign,
 };
 
-use super::store::{self, Store};
+use super::store::{self, ArrayStore, BitmapStore, IntervalStore, Store};
 use super::util;
 
 pub const ARRAY_LIMIT: u64 = 4096;
@@ -23,6 +23,12 @@ pub struct Iter<'a> {
     inner: store::Iter<'a>,
 }
 
+#[derive(Clone)]
+pub struct Ranges<'a> {
+    key: u16,
+    inner: store::Ranges<'a>,
+}
+
 impl Container {
     pub fn new(key: u16) -> Container {
         Container { key, store: Store::new() }
@@ -32,6 +38,11 @@ impl Container {
         Container { key, store: Store::full() }
     }
 
+    /// A full container stored as a single run rather than an 8 KiB bitset.
+    pub fn full_run(key: u16) -> Container {
+        Container { key, store: Store::Run(IntervalStore::full()) }
+    }
+
     pub fn from_lsb0_bytes(key: u16, bytes: &[u8], byte_offset: usize) -> Option<Self> {
         Some(Container { key, store: Store::from_lsb0_bytes(bytes, byte_offset)? })
     }
@@ -107,6 +118,22 @@ impl Container {
         result
     }
 
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) {
+        // If flipping the range can make this a bitmap by itself, do it now
+        if range.len() as u64 > ARRAY_LIMIT {
+            if let Store::Array(arr) = &self.store {
+                self.store = Store::Bitmap(arr.to_bitmap_store());
+            }
+        }
+        self.store.flip_range(range);
+        self.ensure_correct_store();
+    }
+
+    pub fn retain(&mut self, f: impl FnMut(u16) -> bool) {
+        self.store.retain(f);
+        self.ensure_correct_store();
+    }
+
     pub fn remove_smallest(&mut self, n: u64) {
         match &self.store {
             Store::Bitmap(bits) => {
@@ -118,7 +145,7 @@ impl Container {
                     self.store.remove_smallest(n)
                 }
             }
-            Store::Array(_) => self.store.remove_smallest(n),
+            Store::Array(_) | Store::Run(_) => self.store.remove_smallest(n),
         };
     }
 
@@ -133,7 +160,7 @@ impl Container {
                     self.store.remove_biggest(n)
                 }
             }
-            Store::Array(_) => self.store.remove_biggest(n),
+            Store::Array(_) | Store::Run(_) => self.store.remove_biggest(n),
         };
     }
 
@@ -174,6 +201,10 @@ impl Container {
         self.store.rank(index)
     }
 
+    pub fn ranges(&self) -> Ranges<'_> {
+        Ranges { key: self.key, inner: self.store.ranges() }
+    }
+
     pub(crate) fn ensure_correct_store(&mut self) {
         match &self.store {
             Store::Bitmap(ref bits) => {
@@ -186,8 +217,66 @@ impl Container {
                     self.store = Store::Bitmap(vec.to_bitmap_store())
                 }
             }
+            Store::Run(ref runs) => {
+                // Runs are only kept while they are the most compact representation
+                let len = runs.len();
+                let size_as_array = ArrayStore::serialized_byte_size(len);
+                let size_as_bitmap = BitmapStore::serialized_byte_size();
+                if runs.byte_size() > size_as_array.min(size_as_bitmap) {
+                    self.store = if len <= ARRAY_LIMIT {
+                        Store::Array(runs.to_array_store())
+                    } else {
+                        Store::Bitmap(runs.to_bitmap_store())
+                    }
+                }
+            }
         };
     }
+
+    /// Converts the store to whichever of array, bitmap or runs takes the least
+    /// space and returns whether the store changed.
+    pub fn optimize(&mut self) -> bool {
+        match &self.store {
+            Store::Array(ref vec) => {
+                let size_as_run =
+                    IntervalStore::serialized_byte_size(IntervalStore::count_runs_array(vec));
+                if ArrayStore::serialized_byte_size(vec.len()) <= size_as_run {
+                    return false;
+                }
+                self.store = Store::Run(IntervalStore::from_array_store(vec));
+                true
+            }
+            Store::Bitmap(ref bits) => {
+                let size_as_run =
+                    IntervalStore::serialized_byte_size(IntervalStore::count_runs_bitmap(bits));
+                if BitmapStore::serialized_byte_size() <= size_as_run {
+                    return false;
+                }
+                self.store = Store::Run(IntervalStore::from_bitmap_store(bits));
+                true
+            }
+            Store::Run(_) => {
+                self.ensure_correct_store();
+                !matches!(self.store, Store::Run(_))
+            }
+        }
+    }
+
+    /// Converts a run store back to an array or a bitmap store and returns
+    /// whether the store changed.
+    pub fn remove_run_compression(&mut self) -> bool {
+        match &self.store {
+            Store::Run(ref runs) => {
+                self.store = if runs.len() <= ARRAY_LIMIT {
+                    Store::Array(runs.to_array_store())
+                } else {
+                    Store::Bitmap(runs.to_bitmap_store())
+                };
+                true
+            }
+            Store::Array(_) | Store::Bitmap(_) => false,
+        }
+    }
 }
 
 impl BitOr<&Container> for &Container {
@@ -333,6 +422,10 @@ impl DoubleEndedIterator for Iter<'_> {
 impl ExactSizeIterator for Iter<'_> {}
 
 impl Iter<'_> {
+    pub(crate) fn next_many(&mut self, dst: &mut [u32]) -> usize {
+        self.inner.next_many(u32::from(self.key) << 16, dst)
+    }
+
     pub(crate) fn advance_to(&mut self, index: u16) {
         self.inner.advance_to(index);
     }
@@ -342,6 +435,22 @@ impl Iter<'_> {
     }
 }
 
+impl Iterator for Ranges<'_> {
+    type Item = RangeInclusive<u32>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u32>> {
+        let range = self.inner.next()?;
+        Some(util::join(self.key, *range.start())..=util::join(self.key, *range.end()))
+    }
+}
+
+impl DoubleEndedIterator for Ranges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u32>> {
+        let range = self.inner.next_back()?;
+        Some(util::join(self.key, *range.start())..=util::join(self.key, *range.end()))
+    }
+}
+
 impl fmt::Debug for Container {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         format!("Container<{:?} @ {:?}>", self.len(), self.key).fmt(formatter)
@@ -1,7 +1,8 @@
 use core::cmp::Ordering;
-use core::ops::RangeBounds;
+use core::mem;
+use core::ops::{BitOrAssign, RangeBounds};
 
-use crate::RoaringBitmap;
+use crate::{OverlappingValues, RoaringBitmap};
 
 use super::container::Container;
 use super::util;
@@ -76,6 +77,13 @@ impl RoaringBitmap {
         }
     }
 
+    /// Searches for the specific container by the given key.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn find_container(&self, key: u16) -> Option<&Container> {
+        self.containers.binary_search_by_key(&key, |c| c.key).ok().map(|loc| &self.containers[loc])
+    }
+
     /// Inserts a range of values.
     /// Returns the number of inserted values.
     ///
@@ -209,6 +217,7 @@ impl RoaringBitmap {
     /// assert_eq!(rb.contains(3), false);
     /// ```
     #[inline]
+    #[allow(clippy::collapsible_match)]
     pub fn remove(&mut self, value: u32) -> bool {
         let (key, index) = util::split(value);
         match self.containers.binary_search_by_key(&key, |c| c.key) {
@@ -270,6 +279,362 @@ impl RoaringBitmap {
         removed
     }
 
+    /// Inserts the value if it is absent from the set, removes it otherwise.
+    ///
+    /// Returns whether the value is in the set after the call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// assert_eq!(rb.flip(3), true);
+    /// assert_eq!(rb.contains(3), true);
+    /// assert_eq!(rb.flip(3), false);
+    /// assert_eq!(rb.contains(3), false);
+    /// ```
+    #[inline]
+    pub fn flip(&mut self, value: u32) -> bool {
+        if self.remove(value) {
+            false
+        } else {
+            self.insert(value)
+        }
+    }
+
+    /// Toggles every value in the range, inserting the absent values and removing the
+    /// present ones. The absent containers covered by the whole range are stored as runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).collect();
+    /// rb.flip_range(5..15);
+    /// assert_eq!(rb, (0..5).chain(10..15).collect());
+    /// ```
+    pub fn flip_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => return,
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let first = self.containers.partition_point(|c| c.key < start_container_key);
+        let last = self.containers.partition_point(|c| c.key <= end_container_key);
+        let mut existing =
+            self.containers.drain(first..last).collect::<Vec<_>>().into_iter().peekable();
+
+        let mut flipped = Vec::new();
+        for key in start_container_key..=end_container_key {
+            let a = if key == start_container_key { start_index } else { 0 };
+            let b = if key == end_container_key { end_index } else { u16::MAX };
+            let mut container = match existing.next_if(|c| c.key == key) {
+                Some(container) => container,
+                // Flipping a whole missing container is the same as filling it
+                None if a == 0 && b == u16::MAX => {
+                    flipped.push(Container::full_run(key));
+                    continue;
+                }
+                None => Container::new(key),
+            };
+            container.flip_range(a..=b);
+            if !container.is_empty() {
+                flipped.push(container);
+            }
+        }
+
+        self.containers.splice(first..first, flipped);
+    }
+
+    /// Returns the values of the range that are absent from this set,
+    /// without building the range as a bitmap. The absent containers covered by the whole
+    /// range are stored as runs.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).collect();
+    /// assert_eq!(rb.complement_within(5..15), (10..15).collect());
+    /// ```
+    pub fn complement_within<R>(&self, range: R) -> RoaringBitmap
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => return RoaringBitmap::new(),
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let first = self.containers.partition_point(|c| c.key < start_container_key);
+        let last = self.containers.partition_point(|c| c.key <= end_container_key);
+        let mut existing = self.containers[first..last].iter().peekable();
+
+        let mut containers = Vec::new();
+        for key in start_container_key..=end_container_key {
+            let a = if key == start_container_key { start_index } else { 0 };
+            let b = if key == end_container_key { end_index } else { u16::MAX };
+            let mut container = match existing.next_if(|c| c.key == key) {
+                Some(container) => {
+                    let mut container = container.clone();
+                    if a > 0 {
+                        container.remove_range(0..=a - 1);
+                    }
+                    if b < u16::MAX {
+                        container.remove_range(b + 1..=u16::MAX);
+                    }
+                    container
+                }
+                None if a == 0 && b == u16::MAX => {
+                    containers.push(Container::full_run(key));
+                    continue;
+                }
+                None => Container::new(key),
+            };
+            container.flip_range(a..=b);
+            if !container.is_empty() {
+                containers.push(container);
+            }
+        }
+
+        RoaringBitmap { containers }
+    }
+
+    /// Returns the values of this set that are in the range, without building the
+    /// range as a bitmap. The containers lying fully inside the range are cloned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(200_000..200_010).collect();
+    /// assert_eq!(rb.intersection_with_range(5..200_005), (5..10).chain(200_000..200_005).collect());
+    /// ```
+    pub fn intersection_with_range<R>(&self, range: R) -> RoaringBitmap
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => return RoaringBitmap::new(),
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let first = self.containers.partition_point(|c| c.key < start_container_key);
+        let last = self.containers.partition_point(|c| c.key <= end_container_key);
+        let mut containers = self.containers[first..last].to_vec();
+        trim_to_range(
+            &mut containers,
+            start_container_key,
+            start_index,
+            end_container_key,
+            end_index,
+        );
+
+        RoaringBitmap { containers }
+    }
+
+    /// Removes the values of this set that are outside of the range and returns the number
+    /// of removed values. The containers lying fully inside the range are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).chain(200_000..200_010).collect();
+    /// assert_eq!(rb.retain_range(5..200_005), 10);
+    /// assert_eq!(rb, (5..10).chain(200_000..200_005).collect());
+    /// ```
+    pub fn retain_range<R>(&mut self, range: R) -> u64
+    where
+        R: RangeBounds<u32>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Ok(range) => (*range.start(), *range.end()),
+            Err(_) => {
+                let removed = self.len();
+                self.clear();
+                return removed;
+            }
+        };
+
+        let (start_container_key, start_index) = util::split(start);
+        let (end_container_key, end_index) = util::split(end);
+
+        let previous_len = self.len();
+        let last = self.containers.partition_point(|c| c.key <= end_container_key);
+        self.containers.truncate(last);
+        let first = self.containers.partition_point(|c| c.key < start_container_key);
+        self.containers.drain(..first);
+        trim_to_range(
+            &mut self.containers,
+            start_container_key,
+            start_index,
+            end_container_key,
+            end_index,
+        );
+
+        previous_len - self.len()
+    }
+
+    /// Splits the set in two at the given value, returns everything after the given value,
+    /// including the value itself.
+    ///
+    /// Only the container holding the value is split, the following ones are moved
+    /// to the new set without being copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).chain(200_000..200_010).collect();
+    /// let split = rb.split_off(5);
+    ///
+    /// assert_eq!(rb, (0..5).collect());
+    /// assert_eq!(split, (5..10).chain(200_000..200_010).collect());
+    /// ```
+    pub fn split_off(&mut self, at: u32) -> RoaringBitmap {
+        let (key, index) = util::split(at);
+        let first = self.containers.partition_point(|c| c.key < key);
+        let mut containers = self.containers.split_off(first);
+
+        if let Some(container) = containers.first_mut().filter(|c| c.key == key && index > 0) {
+            let mut lower = container.clone();
+            lower.remove_range(index..=u16::MAX);
+            container.remove_range(0..=index - 1);
+            if !lower.is_empty() {
+                self.containers.push(lower);
+            }
+            if container.is_empty() {
+                containers.remove(0);
+            }
+        }
+
+        RoaringBitmap { containers }
+    }
+
+    /// Moves all the values of `other` at the end of this set, leaving `other` empty.
+    ///
+    /// The values of `other` must all be greater than the greatest value in the set,
+    /// the containers of `other` are then moved without being copied. Otherwise nothing
+    /// is moved and an error is returned.
+    ///
+    /// Returns `Ok` with the number of values appended to the set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).collect();
+    /// let mut other: RoaringBitmap = (10..20).chain(200_000..200_010).collect();
+    ///
+    /// assert_eq!(rb.append_bitmap(&mut other), Ok(20));
+    /// assert_eq!(rb, (0..20).chain(200_000..200_010).collect());
+    /// assert!(other.is_empty());
+    ///
+    /// let mut other: RoaringBitmap = (5..15).collect();
+    /// let error = rb.append_bitmap(&mut other).unwrap_err();
+    /// assert_eq!((error.max(), error.appended_min()), (200_009, 5));
+    /// assert_eq!(other.len(), 10);
+    /// ```
+    pub fn append_bitmap(&mut self, other: &mut RoaringBitmap) -> Result<u64, OverlappingValues> {
+        if let (Some(max), Some(min)) = (self.max(), other.min()) {
+            if min <= max {
+                return Err(OverlappingValues {
+                    max: u64::from(max),
+                    appended_min: u64::from(min),
+                });
+            }
+        }
+
+        let appended = other.len();
+        let mut containers = mem::take(&mut other.containers).into_iter();
+        match (self.containers.last_mut(), containers.as_slice().first()) {
+            (Some(last), Some(first)) if last.key == first.key => {
+                // The values are disjoint, the union is a concatenation
+                BitOrAssign::bitor_assign(last, containers.next().unwrap());
+            }
+            _ => (),
+        }
+        self.containers.extend(containers);
+
+        Ok(appended)
+    }
+
+    /// Returns a new set with every value of this set shifted by the offset,
+    /// the values shifted out of the `u32` range are dropped.
+    ///
+    /// When the offset is a multiple of 65536 only the keys of the containers are rewritten,
+    /// otherwise the values of each container are split between the two neighbouring keys.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = [0, 10, 65_530, u32::MAX - 5].into_iter().collect();
+    ///
+    /// assert_eq!(rb.add_offset(10), [10, 20, 65_540].into_iter().collect());
+    /// assert_eq!(rb.add_offset(-10), [0, 65_520, u32::MAX - 15].into_iter().collect());
+    /// assert_eq!(rb.add_offset(1 << 16), [1 << 16, 10 + (1 << 16), 65_530 + (1 << 16)].into_iter().collect());
+    /// ```
+    pub fn add_offset(&self, offset: i64) -> RoaringBitmap {
+        let key_offset = offset >> 16;
+        let index_offset = offset as u16;
+        let shifted_key = |key: u16, carry: i64| u16::try_from(i64::from(key) + key_offset + carry);
+
+        if index_offset == 0 {
+            let containers = self
+                .containers
+                .iter()
+                .filter_map(|c| {
+                    Some(Container { key: shifted_key(c.key, 0).ok()?, store: c.store.clone() })
+                })
+                .collect();
+            return RoaringBitmap { containers };
+        }
+
+        let mut containers: Vec<Container> = Vec::with_capacity(self.containers.len() + 1);
+        for container in &self.containers {
+            let (low, high) = container.store.add_offset(index_offset);
+            for (carry, store) in [(0, low), (1, high)] {
+                let Ok(key) = shifted_key(container.key, carry) else { continue };
+                let mut shifted = Container { key, store };
+                if shifted.is_empty() {
+                    continue;
+                }
+                match containers.last_mut() {
+                    // The values overflowing from the previous container
+                    Some(last) if last.key == key => BitOrAssign::bitor_assign(last, shifted),
+                    _ => {
+                        shifted.ensure_correct_store();
+                        containers.push(shifted);
+                    }
+                }
+            }
+        }
+
+        RoaringBitmap { containers }
+    }
+
     /// Returns `true` if this set contains the specified integer.
     ///
     /// # Examples
@@ -419,6 +784,82 @@ impl RoaringBitmap {
         cardinality
     }
 
+    /// Computes the len of the union of this set with the passed range,
+    /// without building the range as a bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).collect();
+    /// let range: RoaringBitmap = (5..15).collect();
+    ///
+    /// assert_eq!(rb.union_with_range_len(5..15), (rb | range).len());
+    /// ```
+    pub fn union_with_range_len<R>(&self, range: R) -> u64
+    where
+        R: RangeBounds<u32>,
+    {
+        let (range_len, intersection_len) = self.range_len_and_cardinality(range);
+        self.len() + range_len - intersection_len
+    }
+
+    /// Computes the len of the difference of this set with the passed range,
+    /// without building the range as a bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).collect();
+    /// let range: RoaringBitmap = (5..15).collect();
+    ///
+    /// assert_eq!(rb.difference_with_range_len(5..15), (rb - range).len());
+    /// ```
+    pub fn difference_with_range_len<R>(&self, range: R) -> u64
+    where
+        R: RangeBounds<u32>,
+    {
+        self.len() - self.range_cardinality(range)
+    }
+
+    /// Computes the len of the symmetric difference of this set with the passed range,
+    /// without building the range as a bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).collect();
+    /// let range: RoaringBitmap = (5..15).collect();
+    ///
+    /// assert_eq!(rb.symmetric_difference_with_range_len(5..15), (rb ^ range).len());
+    /// ```
+    pub fn symmetric_difference_with_range_len<R>(&self, range: R) -> u64
+    where
+        R: RangeBounds<u32>,
+    {
+        let (range_len, intersection_len) = self.range_len_and_cardinality(range);
+        self.len() + range_len - 2 * intersection_len
+    }
+
+    /// Returns the number of values in the range and the number of them in this set.
+    fn range_len_and_cardinality<R>(&self, range: R) -> (u64, u64)
+    where
+        R: RangeBounds<u32>,
+    {
+        match util::convert_range_to_inclusive(range) {
+            Ok(range) => {
+                let range_len = u64::from(*range.end() - *range.start()) + 1;
+                (range_len, self.range_cardinality(range))
+            }
+            Err(_) => (0, 0),
+        }
+    }
+
     /// Clears all integers in this set.
     ///
     /// # Examples
@@ -600,6 +1041,84 @@ impl RoaringBitmap {
         None
     }
 
+    /// Returns an iterator over the number of integers that are <= each of the values.
+    ///
+    /// The cumulative cardinality of the containers is kept across the values, so a sorted
+    /// input is ranked in a single sweep over the containers. A value smaller than the
+    /// previous one restarts the sweep from the first container.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(100_000..100_010).collect();
+    /// let ranks: Vec<u64> = rb.rank_many(&[0, 9, 50_000, 100_005, u32::MAX]).collect();
+    ///
+    /// assert_eq!(ranks, vec![1, 10, 10, 16, 20]);
+    /// ```
+    pub fn rank_many<'a>(&'a self, values: &'a [u32]) -> impl Iterator<Item = u64> + 'a {
+        // The containers before `position` have a key lower than the last value
+        // and `cumulative` is the sum of their lengths.
+        let mut position = 0;
+        let mut cumulative = 0;
+        values.iter().map(move |&value| {
+            let (key, index) = util::split(value);
+            if position > 0 && self.containers[position - 1].key >= key {
+                position = 0;
+                cumulative = 0;
+            }
+            while let Some(container) = self.containers.get(position).filter(|c| c.key < key) {
+                cumulative += container.len();
+                position += 1;
+            }
+            match self.containers.get(position) {
+                Some(container) if container.key == key => cumulative + container.rank(index),
+                _ => cumulative,
+            }
+        })
+    }
+
+    /// Returns an iterator over the `n`th integer in the set for each of the ranks, or `None`
+    /// if `n >= len()`.
+    ///
+    /// The cumulative cardinality of the containers is kept across the ranks, so a sorted
+    /// input is selected in a single sweep over the containers. A rank smaller than the
+    /// previous one restarts the sweep from the first container.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(100_000..100_010).collect();
+    /// let values: Vec<Option<u32>> = rb.select_many(&[0, 9, 10, 19, 20]).collect();
+    ///
+    /// assert_eq!(values, vec![Some(0), Some(9), Some(100_000), Some(100_009), None]);
+    /// ```
+    pub fn select_many<'a>(&'a self, ranks: &'a [u32]) -> impl Iterator<Item = Option<u32>> + 'a {
+        // `cumulative` is the sum of the lengths of the containers before `position`.
+        let mut position = 0;
+        let mut cumulative = 0;
+        ranks.iter().map(move |&n| {
+            let n = u64::from(n);
+            if n < cumulative {
+                position = 0;
+                cumulative = 0;
+            }
+            while let Some(container) = self.containers.get(position) {
+                let len = container.len();
+                if cumulative + len > n {
+                    let index = container.store.select((n - cumulative) as u16)?;
+                    return Some(util::join(container.key, index));
+                }
+                cumulative += len;
+                position += 1;
+            }
+            None
+        })
+    }
+
     /// Removes the `n` smallests values from this bitmap.
     ///
     /// # Examples
@@ -671,6 +1190,137 @@ impl RoaringBitmap {
             self.containers.clear();
         }
     }
+
+    /// Converts every container to whichever of the array, bitmap or run-length
+    /// encodings is the most compact, like `runOptimize` in the Java implementation.
+    ///
+    /// Returns `true` if at least one container changed its encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from_iter(0..100_000);
+    /// assert!(rb.optimize());
+    /// assert_eq!(rb.statistics().n_run_containers, 2);
+    /// assert!(!rb.optimize());
+    /// ```
+    pub fn optimize(&mut self) -> bool {
+        let mut changed = false;
+        for container in &mut self.containers {
+            changed |= container.optimize();
+        }
+        changed
+    }
+
+    /// Converts every run-length encoded container back to an array or a bitmap,
+    /// like `removeRunCompression` in the Java implementation.
+    ///
+    /// Returns `true` if at least one container changed its encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::from_iter(0..100_000);
+    /// rb.optimize();
+    /// assert!(rb.remove_run_compression());
+    /// assert_eq!(rb.statistics().n_run_containers, 0);
+    /// assert_eq!(rb, RoaringBitmap::from_iter(0..100_000));
+    /// ```
+    pub fn remove_run_compression(&mut self) -> bool {
+        let mut changed = false;
+        for container in &mut self.containers {
+            changed |= container.remove_run_compression();
+        }
+        changed
+    }
+
+    /// Retains only the values specified by the predicate.
+    ///
+    /// The values are visited in ascending order, empty containers are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).collect();
+    /// rb.retain(|value| value % 2 == 0);
+    /// assert_eq!(rb, [0, 2, 4, 6, 8].into_iter().collect());
+    /// ```
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(u32) -> bool,
+    {
+        self.containers.retain_mut(|container| {
+            let key = container.key;
+            container.retain(|index| f(util::join(key, index)));
+            !container.is_empty()
+        });
+    }
+
+    /// Removes the values specified by the predicate and returns them.
+    ///
+    /// The values are visited in ascending order, empty containers are dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).collect();
+    /// let odd = rb.extract_if(|value| value % 2 == 1);
+    /// assert_eq!(rb, [0, 2, 4, 6, 8].into_iter().collect());
+    /// assert_eq!(odd, [1, 3, 5, 7, 9].into_iter().collect());
+    /// ```
+    pub fn extract_if<F>(&mut self, mut f: F) -> RoaringBitmap
+    where
+        F: FnMut(u32) -> bool,
+    {
+        let mut extracted = Vec::new();
+        self.containers.retain_mut(|container| {
+            let key = container.key;
+            let mut removed = Container::new(key);
+            container.retain(|index| {
+                if f(util::join(key, index)) {
+                    removed.push_unchecked(index);
+                    false
+                } else {
+                    true
+                }
+            });
+            if !removed.is_empty() {
+                extracted.push(removed);
+            }
+            !container.is_empty()
+        });
+        RoaringBitmap { containers: extracted }
+    }
+}
+
+/// Removes the values outside of the range from the containers of its keys,
+/// only the first and last containers can hold such values.
+fn trim_to_range(
+    containers: &mut Vec<Container>,
+    start_container_key: u16,
+    start_index: u16,
+    end_container_key: u16,
+    end_index: u16,
+) {
+    if let Some(container) = containers.first_mut() {
+        if container.key == start_container_key && start_index > 0 {
+            container.remove_range(0..=start_index - 1);
+        }
+    }
+    if let Some(container) = containers.last_mut() {
+        if container.key == end_container_key && end_index < u16::MAX {
+            container.remove_range(end_index + 1..=u16::MAX);
+        }
+    }
+    containers.retain(|container| !container.is_empty());
 }
 
 impl Default for RoaringBitmap {
@@ -695,6 +1345,7 @@ mod tests {
     use proptest::prelude::*;
 
     use super::*;
+    use crate::bitmap::store::Store;
 
     proptest! {
         #[test]
@@ -945,4 +1596,111 @@ mod tests {
         bitmap.remove_biggest(4);
         assert_eq!(bitmap, RoaringBitmap::default());
     }
+
+    proptest! {
+        #[test]
+        fn retain_and_extract_if_match_filter(
+            mut bitmap in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+            modulo in 1u32..=7,
+        ) {
+            if optimize {
+                bitmap.optimize();
+            }
+            let kept: Vec<u32> = bitmap.iter().filter(|v| v % modulo == 0).collect();
+            let removed: Vec<u32> = bitmap.iter().filter(|v| v % modulo != 0).collect();
+
+            let mut retained = bitmap.clone();
+            retained.retain(|v| v % modulo == 0);
+            prop_assert!(retained.iter().eq(kept.iter().copied()));
+
+            let extracted = bitmap.extract_if(|v| v % modulo != 0);
+            prop_assert!(bitmap.iter().eq(kept.iter().copied()));
+            prop_assert!(extracted.iter().eq(removed.iter().copied()));
+
+            for rb in [&retained, &bitmap, &extracted] {
+                for container in &rb.containers {
+                    prop_assert!(!container.is_empty());
+                    if let Store::Bitmap(_) = container.store {
+                        prop_assert!(container.len() > 4096);
+                    }
+                }
+            }
+        }
+
+        #[test]
+        fn split_off_and_append_bitmap_round_trip(
+            bitmap in RoaringBitmap::arbitrary(),
+            at in 0u32..0x12_0000,
+        ) {
+            let mut lower = bitmap.clone();
+            let mut upper = lower.split_off(at);
+            prop_assert!(lower.iter().eq(bitmap.range(..at)));
+            prop_assert!(upper.iter().eq(bitmap.range(at..)));
+            for container in lower.containers.iter().chain(&upper.containers) {
+                prop_assert!(!container.is_empty());
+            }
+
+            let upper_len = upper.len();
+            prop_assert_eq!(lower.append_bitmap(&mut upper), Ok(upper_len));
+            prop_assert_eq!(&lower, &bitmap);
+            prop_assert!(upper.is_empty());
+        }
+
+        #[test]
+        fn add_offset_shifts_values(
+            mut bitmap in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+            offset in prop_oneof![
+                -0x20_0000i64..0x20_0000,
+                (-0x20i64..0x20).prop_map(|keys| keys << 16),
+                Just(-(1i64 << 32)),
+                Just(u32::MAX as i64),
+            ],
+        ) {
+            if optimize {
+                bitmap.optimize();
+            }
+            let shifted = bitmap.add_offset(offset);
+            let expected = bitmap.iter().filter_map(|v| u32::try_from(i64::from(v) + offset).ok());
+            prop_assert!(shifted.iter().eq(expected));
+            for container in &shifted.containers {
+                prop_assert!(!container.is_empty());
+                if let Store::Bitmap(_) = container.store {
+                    prop_assert!(container.len() > 4096);
+                }
+            }
+        }
+
+        #[test]
+        fn optimize_keeps_values(bitmap in RoaringBitmap::arbitrary()) {
+            let mut optimized = bitmap.clone();
+            optimized.optimize();
+            prop_assert_eq!(optimized.len(), bitmap.len());
+            prop_assert!(optimized.iter().eq(bitmap.iter()));
+            prop_assert!(!optimized.optimize());
+
+            optimized.remove_run_compression();
+            prop_assert_eq!(optimized.statistics().n_run_containers, 0);
+            prop_assert!(optimized.iter().eq(bitmap.iter()));
+        }
+    }
+
+    #[test]
+    fn optimize_picks_smallest_store() {
+        let mut bitmap = RoaringBitmap::new();
+        // a sparse array, a single run, a dense bitmap with many runs
+        bitmap.extend((0..100).map(|i| i * 7));
+        bitmap.insert_range(0x1_0000..0x1_8000);
+        bitmap.extend((0x2_0000..0x3_0000).filter(|i| i % 3 != 0));
+
+        assert!(bitmap.optimize());
+        assert!(matches!(bitmap.containers[0].store, Store::Array(_)));
+        assert!(matches!(bitmap.containers[1].store, Store::Run(_)));
+        assert!(matches!(bitmap.containers[2].store, Store::Bitmap(_)));
+
+        assert!(bitmap.remove_run_compression());
+        assert!(matches!(bitmap.containers[1].store, Store::Bitmap(_)));
+        assert!(!bitmap.remove_run_compression());
+    }
 }
@@ -1,10 +1,12 @@
 use alloc::vec;
-use core::iter::FusedIterator;
-use core::ops::RangeBounds;
+use core::iter::{self, FusedIterator};
+use core::mem;
+use core::ops::{BitOrAssign, RangeBounds, RangeInclusive};
 use core::slice;
 
 use super::container::Container;
-use super::{container, util};
+use super::store::{ArrayStore, BitmapStore, Interval, IntervalStore, Store};
+use super::{container, util, Pairs};
 use crate::{NonSortedIntegers, RoaringBitmap};
 
 #[cfg(not(feature = "std"))]
@@ -26,6 +28,24 @@ pub struct IntoIter {
     back: Option<container::Iter<'static>>,
 }
 
+type ContainerRanges<'a> = iter::FlatMap<
+    slice::Iter<'a, Container>,
+    container::Ranges<'a>,
+    fn(&'a Container) -> container::Ranges<'a>,
+>;
+
+/// An iterator over the runs of consecutive values of a `RoaringBitmap`.
+///
+/// This struct is created by the [`RoaringBitmap::ranges`] method.
+#[derive(Clone)]
+pub struct Ranges<'a> {
+    inner: ContainerRanges<'a>,
+    // The ranges of the containers are merged when they touch,
+    // the ones read while looking for the end of a run are kept here.
+    peeked_front: Option<RangeInclusive<u32>>,
+    peeked_back: Option<RangeInclusive<u32>>,
+}
+
 #[inline]
 fn and_then_or_clear<T, U>(opt: &mut Option<T>, f: impl FnOnce(&mut T) -> Option<U>) -> Option<U> {
     let x = f(opt.as_mut()?);
@@ -35,6 +55,37 @@ fn and_then_or_clear<T, U>(opt: &mut Option<T>, f: impl FnOnce(&mut T) -> Option
     x
 }
 
+fn next_many_impl<'a, It>(
+    dst: &mut [u32],
+    front_iter: &mut Option<container::Iter<'a>>,
+    containers: &mut It,
+    back_iter: &mut Option<container::Iter<'a>>,
+) -> usize
+where
+    It: Iterator,
+    It::Item: IntoIterator<IntoIter = container::Iter<'a>>,
+{
+    let mut count = 0;
+    loop {
+        if let Some(iter) = front_iter {
+            count += iter.next_many(&mut dst[count..]);
+            if count == dst.len() {
+                return count;
+            }
+        }
+        // The front iterator is exhausted
+        *front_iter = match containers.next() {
+            Some(container) => Some(container.into_iter()),
+            None => {
+                if let Some(iter) = back_iter {
+                    count += iter.next_many(&mut dst[count..]);
+                }
+                return count;
+            }
+        }
+    }
+}
+
 fn advance_to_impl<'a, It>(
     n: u32,
     front_iter: &mut Option<container::Iter<'a>>,
@@ -152,7 +203,7 @@ fn advance_back_to_impl<'a, It>(
 }
 
 impl Iter<'_> {
-    fn new(containers: &[Container]) -> Iter {
+    fn new(containers: &[Container]) -> Iter<'_> {
         Iter { front: None, containers: containers.iter(), back: None }
     }
 
@@ -160,6 +211,30 @@ impl Iter<'_> {
         Self::new(&[])
     }
 
+    /// Fills the buffer with the next values of the iterator and returns the number of values
+    /// written, which is lower than the length of the buffer only when the iterator is exhausted.
+    ///
+    /// This is expected to be faster than calling [`Iterator::next`] for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (0..10).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.iter();
+    /// iter.advance_to(2);
+    ///
+    /// let mut buffer = [0; 5];
+    /// assert_eq!(iter.next_many(&mut buffer), 5);
+    /// assert_eq!(buffer, [2, 3, 4, 5, 6]);
+    /// assert_eq!(iter.next_many(&mut buffer), 3);
+    /// assert_eq!(&buffer[..3], [7, 8, 9]);
+    /// ```
+    pub fn next_many(&mut self, dst: &mut [u32]) -> usize {
+        next_many_impl(dst, &mut self.front, &mut self.containers, &mut self.back)
+    }
+
     /// Advance the iterator to the first position where the item has a value >= `n`
     ///
     /// # Examples
@@ -208,6 +283,30 @@ impl IntoIter {
         Self::new(Vec::new())
     }
 
+    /// Fills the buffer with the next values of the iterator and returns the number of values
+    /// written, which is lower than the length of the buffer only when the iterator is exhausted.
+    ///
+    /// This is expected to be faster than calling [`Iterator::next`] for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = (0..10).collect::<RoaringBitmap>();
+    /// let mut iter = bitmap.into_iter();
+    /// iter.advance_to(2);
+    ///
+    /// let mut buffer = [0; 5];
+    /// assert_eq!(iter.next_many(&mut buffer), 5);
+    /// assert_eq!(buffer, [2, 3, 4, 5, 6]);
+    /// assert_eq!(iter.next_many(&mut buffer), 3);
+    /// assert_eq!(&buffer[..3], [7, 8, 9]);
+    /// ```
+    pub fn next_many(&mut self, dst: &mut [u32]) -> usize {
+        next_many_impl(dst, &mut self.front, &mut self.containers, &mut self.back)
+    }
+
     /// Advance the iterator to the first position where the item has a value >= `n`
     ///
     /// # Examples
@@ -542,6 +641,58 @@ impl DoubleEndedIterator for IntoIter {
 impl ExactSizeIterator for IntoIter {}
 impl FusedIterator for IntoIter {}
 
+impl Ranges<'_> {
+    fn new(containers: &[Container]) -> Ranges<'_> {
+        Ranges {
+            inner: containers.iter().flat_map(Container::ranges as _),
+            peeked_front: None,
+            peeked_back: None,
+        }
+    }
+}
+
+impl Iterator for Ranges<'_> {
+    type Item = RangeInclusive<u32>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u32>> {
+        let mut range = self
+            .peeked_front
+            .take()
+            .or_else(|| self.inner.next())
+            .or_else(|| self.peeked_back.take())?;
+        while let Some(next) = self.inner.next().or_else(|| self.peeked_back.take()) {
+            if *next.start() - 1 == *range.end() {
+                range = *range.start()..=*next.end();
+            } else {
+                self.peeked_front = Some(next);
+                break;
+            }
+        }
+        Some(range)
+    }
+}
+
+impl DoubleEndedIterator for Ranges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u32>> {
+        let mut range = self
+            .peeked_back
+            .take()
+            .or_else(|| self.inner.next_back())
+            .or_else(|| self.peeked_front.take())?;
+        while let Some(previous) = self.inner.next_back().or_else(|| self.peeked_front.take()) {
+            if *previous.end() + 1 == *range.start() {
+                range = *previous.start()..=*range.end();
+            } else {
+                self.peeked_back = Some(previous);
+                break;
+            }
+        }
+        Some(range)
+    }
+}
+
+impl FusedIterator for Ranges<'_> {}
+
 impl RoaringBitmap {
     /// Iterator over each value stored in the RoaringBitmap, guarantees values are ordered by value.
     ///
@@ -558,7 +709,7 @@ impl RoaringBitmap {
     /// assert_eq!(iter.next(), Some(2));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::new(&self.containers)
     }
 
@@ -672,6 +823,26 @@ impl RoaringBitmap {
         }
         iter
     }
+
+    /// Iterator over the maximal runs of consecutive values stored in the RoaringBitmap,
+    /// guarantees runs are ordered by value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let bitmap = RoaringBitmap::from([1, 2, 3, 7, 65535, 65536, 65537]);
+    /// let mut ranges = bitmap.ranges();
+    ///
+    /// assert_eq!(ranges.next(), Some(1..=3));
+    /// assert_eq!(ranges.next_back(), Some(65535..=65537));
+    /// assert_eq!(ranges.next(), Some(7..=7));
+    /// assert_eq!(ranges.next(), None);
+    /// ```
+    pub fn ranges(&self) -> Ranges<'_> {
+        Ranges::new(&self.containers)
+    }
 }
 
 impl<'a> IntoIterator for &'a RoaringBitmap {
@@ -784,7 +955,150 @@ impl<'a> Extend<&'a u32> for RoaringBitmap {
     }
 }
 
+impl FromIterator<RangeInclusive<u32>> for RoaringBitmap {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<u32>>>(iterator: I) -> RoaringBitmap {
+        let mut rb = RoaringBitmap::new();
+        rb.extend(iterator);
+        rb
+    }
+}
+
+impl Extend<RangeInclusive<u32>> for RoaringBitmap {
+    /// Inserts multiple ranges of values.
+    /// This is expected to be faster than calling [`RoaringBitmap::insert_range`] on each range.
+    ///
+    /// The provided ranges don't have to be in sorted order, but the leading ranges that are
+    /// sorted and greater than the current maximum value are directly appended to the containers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb = RoaringBitmap::new();
+    /// rb.extend([1..=4, 1500..=1509, 100..=200]);
+    /// assert!(rb.contains(2));
+    /// assert!(rb.contains(1508));
+    /// assert!(rb.contains(150));
+    /// assert!(!rb.contains(5));
+    /// ```
+    fn extend<I: IntoIterator<Item = RangeInclusive<u32>>>(&mut self, ranges: I) {
+        let mut ranges = ranges.into_iter();
+        let range = match self.append_sorted_ranges(&mut ranges) {
+            (_, Some(range)) => range,
+            (_, None) => return,
+        };
+
+        // The remaining ranges are inserted one by one, the last container is reused
+        // as long as the ranges stay in it.
+        let mut current: Option<usize> = None;
+        for range in iter::once(range).chain(ranges) {
+            let (start, end) = (*range.start(), *range.end());
+            let (start_key, start_index) = util::split(start);
+            let (end_key, end_index) = util::split(end);
+            if range.is_empty() || start_key != end_key {
+                self.insert_range(range);
+                current = None;
+                continue;
+            }
+            let index = match current {
+                Some(index) if self.containers[index].key == start_key => index,
+                _ => self.find_container_by_key(start_key),
+            };
+            self.containers[index].insert_range(start_index..=end_index);
+            current = Some(index);
+        }
+    }
+}
+
 impl RoaringBitmap {
+    /// Create the set from a sorted iterator of ranges. The ranges must be sorted and must not
+    /// overlap, each container is directly built from the runs that fall into it.
+    ///
+    /// The ranges of the iterator must be ordered and strictly greater than the previous ones.
+    /// If a range doesn't satisfy this requirement the operation is stopped.
+    ///
+    /// Returns `Ok` with the requested `RoaringBitmap`, `Err` with the number of ranges
+    /// that were correctly appended before failure.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_sorted_ranges([0..=9, 20..=29]).unwrap();
+    /// assert!(rb.iter().eq((0..10).chain(20..30)));
+    ///
+    /// let error = RoaringBitmap::from_sorted_ranges([20..=29, 0..=9]).unwrap_err();
+    /// assert_eq!(error.valid_until(), 1);
+    /// ```
+    pub fn from_sorted_ranges<I: IntoIterator<Item = RangeInclusive<u32>>>(
+        iterator: I,
+    ) -> Result<RoaringBitmap, NonSortedIntegers> {
+        let mut rb = RoaringBitmap::new();
+        match rb.append_sorted_ranges(&mut iterator.into_iter()) {
+            (_, None) => Ok(rb),
+            (valid_until, Some(_)) => Err(NonSortedIntegers { valid_until }),
+        }
+    }
+
+    /// Appends the ranges at the end of the set as long as they are sorted and greater than
+    /// the current maximum value.
+    ///
+    /// Returns the number of appended ranges and the first range that could not be appended.
+    fn append_sorted_ranges<I>(&mut self, ranges: &mut I) -> (u64, Option<RangeInclusive<u32>>)
+    where
+        I: Iterator<Item = RangeInclusive<u32>>,
+    {
+        let mut max = self.max();
+        let mut count = 0;
+        let mut rejected = None;
+
+        for range in ranges {
+            if range.is_empty() {
+                count += 1;
+                continue;
+            }
+            let (start, end) = (*range.start(), *range.end());
+            if max.map_or(false, |max| start <= max) {
+                rejected = Some(range);
+                break;
+            }
+            max = Some(end);
+            count += 1;
+
+            let (start_key, start_index) = util::split(start);
+            let (end_key, end_index) = util::split(end);
+            for key in start_key..=end_key {
+                let a = if key == start_key { start_index } else { 0 };
+                let b = if key == end_key { end_index } else { u16::MAX };
+                match self.containers.last_mut() {
+                    Some(container) if container.key == key => match &mut container.store {
+                        Store::Run(runs) => runs.push_interval_unchecked(Interval::new(a, b)),
+                        // Only the first range can fall into a container that was not built here
+                        store => {
+                            store.insert_range(a..=b);
+                        }
+                    },
+                    last => {
+                        // The runs of a container are gathered before choosing its store
+                        if let Some(container) = last {
+                            container.ensure_correct_store();
+                        }
+                        let mut runs = IntervalStore::new();
+                        runs.push_interval_unchecked(Interval::new(a, b));
+                        self.containers.push(Container { key, store: Store::Run(runs) });
+                    }
+                }
+            }
+        }
+
+        if let Some(container) = self.containers.last_mut() {
+            container.ensure_correct_store();
+        }
+        (count, rejected)
+    }
+
     /// Create the set from a sorted iterator. Values must be sorted and deduplicated.
     ///
     /// The values of the iterator must be ordered and strictly greater than the greatest value
@@ -874,4 +1188,139 @@ impl RoaringBitmap {
 
         Ok(count)
     }
+
+    /// Create the set from an unsorted slice of integers, duplicates are allowed.
+    ///
+    /// The values are partitioned by their 16 most significant bits and every container
+    /// is built in one shot, which is expected to be much faster than inserting them one
+    /// by one for large batches.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb = RoaringBitmap::from_unsorted_slice(&[7, 1_000_000, 3, 7, 42]);
+    /// assert!(rb.iter().eq([3, 7, 42, 1_000_000]));
+    /// ```
+    pub fn from_unsorted_slice(values: &[u32]) -> RoaringBitmap {
+        RoaringBitmap { containers: containers_from_unsorted(values) }
+    }
+
+    /// Inserts the values of an unsorted slice and returns the count of new additions.
+    ///
+    /// The values are partitioned by their 16 most significant bits, every container is
+    /// built in one shot and then merged into the set in a single pass.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..10).collect();
+    /// assert_eq!(rb.extend_from_slice(&[200_000, 5, 10, 200_000]), 2);
+    /// assert!(rb.iter().eq((0..11).chain([200_000])));
+    /// ```
+    pub fn extend_from_slice(&mut self, values: &[u32]) -> u64 {
+        let containers = containers_from_unsorted(values);
+        if self.containers.is_empty() {
+            self.containers = containers;
+            return self.len();
+        }
+
+        let mut inserted = 0;
+        let mut merged = Vec::with_capacity(self.containers.len().max(containers.len()));
+        for pair in Pairs::new(mem::take(&mut self.containers), containers) {
+            match pair {
+                (Some(lhs), None) => merged.push(lhs),
+                (None, Some(rhs)) => {
+                    inserted += rhs.len();
+                    merged.push(rhs);
+                }
+                (Some(mut lhs), Some(rhs)) => {
+                    let previous_len = lhs.len();
+                    BitOrAssign::bitor_assign(&mut lhs, rhs);
+                    inserted += lhs.len() - previous_len;
+                    merged.push(lhs);
+                }
+                (None, None) => break,
+            }
+        }
+        self.containers = merged;
+        inserted
+    }
+}
+
+/// Under this number of values sorting the slice is cheaper than bucketing
+/// the values into the 65536 possible containers.
+const UNSORTED_SORT_THRESHOLD: usize = 1 << 16;
+
+/// Builds the sorted containers holding the given unsorted values.
+fn containers_from_unsorted(values: &[u32]) -> Vec<Container> {
+    let mut containers = Vec::new();
+
+    if values.len() < UNSORTED_SORT_THRESHOLD {
+        let mut values = values.to_vec();
+        values.sort_unstable();
+        let mut lows = Vec::new();
+        let mut rest = values.as_slice();
+        while let Some(&first) = rest.first() {
+            let key = util::split(first).0;
+            let len = rest.iter().position(|&value| util::split(value).0 != key);
+            let (chunk, tail) = rest.split_at(len.unwrap_or(rest.len()));
+            lows.clear();
+            lows.extend(chunk.iter().map(|&value| util::split(value).1));
+            containers.push(container_from_lows(key, &mut lows));
+            rest = tail;
+        }
+        return containers;
+    }
+
+    // Count the values of every key, turn the counts into the start of each bucket
+    // and scatter the low bits into them. Afterwards a bucket ends where the next starts.
+    let mut ends = vec![0usize; 1 << 16];
+    for &value in values {
+        ends[usize::from(util::split(value).0)] += 1;
+    }
+    let mut start = 0;
+    for end in ends.iter_mut() {
+        let count = *end;
+        *end = start;
+        start += count;
+    }
+    let mut lows = vec![0u16; values.len()];
+    for &value in values {
+        let (key, low) = util::split(value);
+        let end = &mut ends[usize::from(key)];
+        lows[*end] = low;
+        *end += 1;
+    }
+
+    let mut start = 0;
+    for (key, &end) in (0..=u16::MAX).zip(ends.iter()) {
+        if start != end {
+            containers.push(container_from_lows(key, &mut lows[start..end]));
+        }
+        start = end;
+    }
+    containers
+}
+
+/// Builds a container from a non-empty and unsorted bucket of low bits.
+fn container_from_lows(key: u16, lows: &mut [u16]) -> Container {
+    let store = if lows.len() as u64 <= container::ARRAY_LIMIT {
+        lows.sort_unstable();
+        let mut vec = lows.to_vec();
+        vec.dedup();
+        Store::Array(ArrayStore::from_vec_unchecked(vec))
+    } else {
+        let mut bits = BitmapStore::new();
+        for &low in lows.iter() {
+            bits.insert(low);
+        }
+        Store::Bitmap(bits)
+    };
+    let mut container = Container { key, store };
+    container.ensure_correct_store();
+    container
 }
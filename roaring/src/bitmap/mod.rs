@@ -1,8 +1,10 @@
 mod arbitrary;
+mod bulk;
 mod container;
 mod fmt;
 mod multiops;
 mod proptests;
+mod rank_index;
 mod statistics;
 mod store;
 mod util;
@@ -19,11 +21,25 @@ mod ops_with_serialized;
 mod serde;
 #[cfg(feature = "std")]
 pub(crate) mod serialization;
+#[cfg(feature = "std")]
+mod validation;
+#[cfg(feature = "std")]
+mod view;
 
+pub use self::bulk::BulkContext;
+pub(crate) use self::cmp::cmp_ranges;
 use self::cmp::Pairs;
 pub use self::iter::IntoIter;
 pub use self::iter::Iter;
+pub use self::iter::Ranges;
+pub use self::rank_index::RankIndex;
+#[cfg(feature = "std")]
+pub use self::serialization::DeserializeError;
 pub use self::statistics::Statistics;
+#[cfg(feature = "std")]
+pub use self::validation::{ValidationProblem, ValidationReport};
+#[cfg(feature = "std")]
+pub use self::view::{RoaringBitmapView, ViewIter};
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
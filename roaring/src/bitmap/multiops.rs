@@ -280,8 +280,12 @@ fn merge_container_owned(
             Ok(loc) => {
                 let lhs = &mut lhs[loc];
                 match (&lhs.store, &rhs.store) {
-                    (Store::Array(..), Store::Array(..)) => lhs.store = lhs.store.to_bitmap(),
-                    (Store::Array(..), Store::Bitmap(..)) => mem::swap(lhs, &mut rhs),
+                    (Store::Array(..) | Store::Run(..), Store::Array(..) | Store::Run(..)) => {
+                        lhs.store = lhs.store.to_bitmap()
+                    }
+                    (Store::Array(..) | Store::Run(..), Store::Bitmap(..)) => {
+                        mem::swap(lhs, &mut rhs)
+                    }
                     _ => (),
                 };
                 op(&mut lhs.store, rhs.store);
@@ -332,7 +336,7 @@ fn try_multi_or_ref<'a, E: 'a>(
     // Phase 3: Clean up
     let containers: Vec<_> = containers
         .into_iter()
-        .filter(|container| container.len() > 0)
+        .filter(|container| !container.is_empty())
         .map(|c| {
             // Any borrowed bitmaps or arrays left over get cloned here
             let mut container = c.into_owned();
@@ -373,7 +377,7 @@ fn try_multi_xor_ref<'a, E: 'a>(
     // Phase 3: Clean up
     let containers: Vec<_> = containers
         .into_iter()
-        .filter(|container| container.len() > 0)
+        .filter(|container| !container.is_empty())
         .map(|c| {
             // Any borrowed bitmaps or arrays left over get cloned here
             let mut container = c.into_owned();
@@ -400,15 +404,15 @@ fn merge_container_ref<'a>(
                 // A container that is in containers. Operate on it.
                 let lhs = &mut containers[loc];
                 match (&lhs.store, &rhs.store) {
-                    (Store::Array(..), Store::Array(..)) => {
-                        // We had borrowed an array. Without cloning it, create a new bitmap
+                    (Store::Array(..) | Store::Run(..), Store::Array(..) | Store::Run(..)) => {
+                        // We had borrowed an array or runs. Without cloning it, create a new bitmap
                         // Add all the elements to the new bitmap
                         let mut store = lhs.store.to_bitmap();
                         op(&mut store, &rhs.store);
                         *lhs = Cow::Owned(Container { key: lhs.key, store });
                     }
-                    (Store::Array(..), Store::Bitmap(..)) => {
-                        // We had borrowed an array. Copy the rhs bitmap, add lhs to it
+                    (Store::Array(..) | Store::Run(..), Store::Bitmap(..)) => {
+                        // We had borrowed an array or runs. Copy the rhs bitmap, add lhs to it
                         let mut store = rhs.store.clone();
                         op(&mut store, &lhs.store);
                         *lhs = Cow::Owned(Container { key: lhs.key, store });
@@ -1,5 +1,7 @@
 use core::mem;
-use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign};
+use core::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Sub, SubAssign,
+};
 
 use crate::bitmap::container::Container;
 use crate::bitmap::Pairs;
@@ -102,6 +104,122 @@ impl RoaringBitmap {
             .wrapping_sub(intersection_len)
             .wrapping_sub(intersection_len)
     }
+
+    /// Computes the [Jaccard index](https://en.wikipedia.org/wiki/Jaccard_index) with the
+    /// specified other bitmap, the len of the intersection divided by the len of the union,
+    /// in a single pass and without creating a new bitmap.
+    ///
+    /// Returns `NaN` when both sets are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.jaccard_index(&rb2), 0.25);
+    /// ```
+    pub fn jaccard_index(&self, other: &RoaringBitmap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        intersection_len as f64 / (lhs_len + rhs_len - intersection_len) as f64
+    }
+
+    /// Computes the [Sørensen–Dice coefficient](https://en.wikipedia.org/wiki/Dice-S%C3%B8rensen_coefficient)
+    /// with the specified other bitmap, twice the len of the intersection divided by the sum of
+    /// the lens, in a single pass and without creating a new bitmap.
+    ///
+    /// Returns `NaN` when both sets are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.dice(&rb2), 0.4);
+    /// ```
+    pub fn dice(&self, other: &RoaringBitmap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        (2 * intersection_len) as f64 / (lhs_len + rhs_len) as f64
+    }
+
+    /// Computes the [cosine similarity](https://en.wikipedia.org/wiki/Cosine_similarity) with
+    /// the specified other bitmap, the len of the intersection divided by the geometric mean of
+    /// the lens, in a single pass and without creating a new bitmap.
+    ///
+    /// Returns `NaN` when one of the sets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..5).collect();
+    /// let rb2: RoaringBitmap = (4..5).collect();
+    ///
+    /// assert_eq!(rb1.cosine(&rb2), 0.5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cosine(&self, other: &RoaringBitmap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        intersection_len as f64 / (lhs_len as f64 * rhs_len as f64).sqrt()
+    }
+
+    /// Computes the [overlap coefficient](https://en.wikipedia.org/wiki/Overlap_coefficient)
+    /// with the specified other bitmap, the len of the intersection divided by the len of the
+    /// smallest set, in a single pass and without creating a new bitmap.
+    ///
+    /// Returns `NaN` when one of the sets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.overlap_coefficient(&rb2), 0.5);
+    /// ```
+    pub fn overlap_coefficient(&self, other: &RoaringBitmap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        intersection_len as f64 / lhs_len.min(rhs_len) as f64
+    }
+
+    /// Computes the [Hamming distance](https://en.wikipedia.org/wiki/Hamming_distance) with the
+    /// specified other bitmap, the number of values contained in only one of the sets, in a
+    /// single pass and without creating a new bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.hamming_distance(&rb2), rb1.symmetric_difference_len(&rb2));
+    /// ```
+    pub fn hamming_distance(&self, other: &RoaringBitmap) -> u64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        lhs_len + rhs_len - 2 * intersection_len
+    }
+
+    /// Computes the lens of both sets and the len of their intersection in a single pass.
+    pub(crate) fn lens_and_intersection_len(&self, other: &RoaringBitmap) -> (u64, u64, u64) {
+        Pairs::new(&self.containers, &other.containers)
+            .map(|pair| match pair {
+                (Some(lhs), None) => (lhs.len(), 0, 0),
+                (None, Some(rhs)) => (0, rhs.len(), 0),
+                (Some(lhs), Some(rhs)) => (lhs.len(), rhs.len(), lhs.intersection_len(rhs)),
+                (None, None) => (0, 0, 0),
+            })
+            .fold((0, 0, 0), |(a, b, c), (x, y, z)| (a + x, b + y, c + z))
+    }
 }
 
 impl BitOr<RoaringBitmap> for RoaringBitmap {
@@ -440,6 +558,25 @@ impl BitXorAssign<&RoaringBitmap> for RoaringBitmap {
     }
 }
 
+impl Not for RoaringBitmap {
+    type Output = RoaringBitmap;
+
+    /// A `complement` of the set, over the whole `u32` range.
+    fn not(mut self) -> RoaringBitmap {
+        self.flip_range(..);
+        self
+    }
+}
+
+impl Not for &RoaringBitmap {
+    type Output = RoaringBitmap;
+
+    /// A `complement` of the set, over the whole `u32` range.
+    fn not(self) -> RoaringBitmap {
+        self.complement_within(..)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use crate::{MultiOps, RoaringBitmap};
@@ -480,6 +617,65 @@ mod test {
             prop_assert_eq!(a.symmetric_difference_len(&b), (a ^ b).len());
         }
 
+        #[test]
+        fn similarities_eq_ratios_of_materialized_lens(
+            a in RoaringBitmap::arbitrary(),
+            b in RoaringBitmap::arbitrary()
+        ) {
+            let intersection_len = (&a & &b).len() as f64;
+            let (a_len, b_len) = (a.len() as f64, b.len() as f64);
+            let eq = |lhs: f64, rhs: f64| lhs == rhs || (lhs.is_nan() && rhs.is_nan());
+            prop_assert!(eq(a.jaccard_index(&b), intersection_len / (&a | &b).len() as f64));
+            prop_assert!(eq(a.dice(&b), 2.0 * intersection_len / (a_len + b_len)));
+            #[cfg(feature = "std")]
+            prop_assert!(eq(a.cosine(&b), intersection_len / (a_len * b_len).sqrt()));
+            prop_assert!(eq(a.overlap_coefficient(&b), intersection_len / a_len.min(b_len)));
+            prop_assert_eq!(a.hamming_distance(&b), (a ^ b).len());
+        }
+
+        #[test]
+        fn flip_range_eq_symmetric_difference_with_range(
+            a in RoaringBitmap::arbitrary(),
+            start in 0u32..0x12_0000,
+            len in 0u32..0x3_0000,
+        ) {
+            let range: RoaringBitmap = (start..start + len).collect();
+            let mut flipped = a.clone();
+            flipped.flip_range(start..start + len);
+            prop_assert_eq!(&flipped, &(&a ^ &range));
+            prop_assert_eq!(a.complement_within(start..start + len), &range - &a);
+        }
+
+        #[test]
+        fn range_ops_eq_ops_with_materialized_range(
+            a in RoaringBitmap::arbitrary(),
+            start in 0u32..0x12_0000,
+            len in 0u32..0x3_0000,
+        ) {
+            let range: RoaringBitmap = (start..start + len).collect();
+            let intersection = &a & &range;
+            prop_assert_eq!(&a.intersection_with_range(start..start + len), &intersection);
+            let mut retained = a.clone();
+            prop_assert_eq!(retained.retain_range(start..start + len), a.len() - intersection.len());
+            prop_assert_eq!(&retained, &intersection);
+
+            prop_assert_eq!(a.union_with_range_len(start..start + len), (&a | &range).len());
+            prop_assert_eq!(a.difference_with_range_len(start..start + len), (&a - &range).len());
+            prop_assert_eq!(
+                a.symmetric_difference_with_range_len(start..start + len),
+                (&a ^ &range).len()
+            );
+        }
+
+        #[test]
+        fn not_complements_the_whole_range(a in RoaringBitmap::arbitrary()) {
+            let not = !&a;
+            prop_assert_eq!(not.len(), (1 << 32) - a.len());
+            prop_assert!(not.is_disjoint(&a));
+            prop_assert_eq!(&not, &!a.clone());
+            prop_assert_eq!(!not, a);
+        }
+
         #[test]
         fn all_union_give_the_same_result(
             a in RoaringBitmap::arbitrary(),
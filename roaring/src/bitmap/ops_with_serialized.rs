@@ -1,19 +1,18 @@
 use bytemuck::cast_slice_mut;
 use byteorder::{LittleEndian, ReadBytesExt};
 use core::convert::Infallible;
-use std::error::Error;
 use std::io::{self, SeekFrom};
 use std::mem;
-use std::ops::RangeInclusive;
 
 use crate::bitmap::container::Container;
 use crate::bitmap::serialization::{
-    NO_OFFSET_THRESHOLD, SERIAL_COOKIE, SERIAL_COOKIE_NO_RUNCONTAINER,
+    read_store, run_store_unchecked, DeserializeError, StoreError, NO_OFFSET_THRESHOLD,
+    SERIAL_COOKIE, SERIAL_COOKIE_NO_RUNCONTAINER,
 };
 use crate::RoaringBitmap;
 
 use super::container::ARRAY_LIMIT;
-use super::store::{ArrayStore, BitmapStore, Store, BITMAP_LENGTH};
+use super::store::{ArrayStore, BitmapStore, Interval, IntervalStore, BITMAP_LENGTH};
 
 impl RoaringBitmap {
     /// Computes the intersection between a materialized [`RoaringBitmap`] and a serialized one.
@@ -22,6 +21,59 @@ impl RoaringBitmap {
     /// It reduces the number of deserialized internal container and therefore
     /// the number of allocations and copies of bytes.
     ///
+    /// The serialized bitmap is read from the current position of the reader,
+    /// which is left at the end of it.
+    ///
+    /// This method checks the serialized containers it reads: the values of the arrays must
+    /// be sorted and unique, the cardinalities of the bitsets and runs must match their
+    /// descriptions and the runs must be sorted, non-overlapping and within bounds.
+    /// An error of kind [`io::ErrorKind::InvalidData`] is returned otherwise.
+    /// If reading from a trusted source consider
+    /// [`RoaringBitmap::intersection_with_serialized_unchecked`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.intersection_with_serialized(Cursor::new(&bytes)).unwrap(), &rb1 & &rb2);
+    ///
+    /// // The values of the array container are not sorted anymore
+    /// bytes[16] = 7;
+    /// assert!(rb1.intersection_with_serialized(Cursor::new(&bytes)).is_err());
+    /// ```
+    pub fn intersection_with_serialized<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        RoaringBitmap::intersection_with_serialized_impl(
+            self,
+            other,
+            ArrayStore::try_from,
+            BitmapStore::try_from,
+            IntervalStore::try_from_runs,
+        )
+    }
+
+    /// Computes the intersection between a materialized [`RoaringBitmap`] and a serialized one.
+    ///
+    /// This is faster and more space efficient when you only need the intersection result.
+    /// It reduces the number of deserialized internal container and therefore
+    /// the number of allocations and copies of bytes.
+    ///
+    /// The serialized bitmap is read from the current position of the reader,
+    /// which is left at the end of it.
+    ///
+    /// This method is memory safe but will not check if the serialized data is a valid bitmap,
+    /// see [`RoaringBitmap::intersection_with_serialized`].
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -45,243 +97,579 @@ impl RoaringBitmap {
     where
         R: io::Read + io::Seek,
     {
-        RoaringBitmap::intersection_with_serialized_impl::<R, _, Infallible, _, Infallible>(
+        RoaringBitmap::intersection_with_serialized_impl::<
+            R,
+            _,
+            Infallible,
+            _,
+            Infallible,
+            _,
+            Infallible,
+        >(
             self,
             other,
             |values| Ok(ArrayStore::from_vec_unchecked(values)),
             |len, values| Ok(BitmapStore::from_unchecked(len, values)),
+            |_, intervals| Ok(run_store_unchecked(intervals)),
         )
     }
 
-    fn intersection_with_serialized_impl<R, A, AErr, B, BErr>(
+    /// Computes the union between a materialized [`RoaringBitmap`] and a serialized one.
+    ///
+    /// Every container of the serialized bitmap is read and checked like
+    /// [`RoaringBitmap::deserialize_from`] does. The serialized bitmap is read from
+    /// the current position of the reader, which is left at the end of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.union_with_serialized(Cursor::new(bytes)).unwrap(), rb1 | rb2);
+    /// ```
+    pub fn union_with_serialized<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        let other = read_serialized_checked(other, |_| true)?;
+        Ok(self | other)
+    }
+
+    /// Computes the union between a materialized [`RoaringBitmap`] and a serialized one.
+    ///
+    /// This method is memory safe but will not check if the serialized data is a valid bitmap,
+    /// see [`RoaringBitmap::union_with_serialized`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.union_with_serialized_unchecked(Cursor::new(bytes)).unwrap(), rb1 | rb2);
+    /// ```
+    pub fn union_with_serialized_unchecked<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        let other = read_serialized_unchecked(other, |_| true)?;
+        Ok(self | other)
+    }
+
+    /// Computes the difference between a materialized [`RoaringBitmap`] and a serialized one.
+    ///
+    /// Only the serialized containers with a key present in this bitmap are read and checked
+    /// like [`RoaringBitmap::deserialize_from`] does. The serialized bitmap is read from
+    /// the current position of the reader, which is left at the end of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.difference_with_serialized(Cursor::new(bytes)).unwrap(), rb1 - rb2);
+    /// ```
+    pub fn difference_with_serialized<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        let other = read_serialized_checked(other, |key| self.find_container(key).is_some())?;
+        Ok(self - other)
+    }
+
+    /// Computes the difference between a materialized [`RoaringBitmap`] and a serialized one.
+    ///
+    /// This method is memory safe but will not check if the serialized data is a valid bitmap,
+    /// see [`RoaringBitmap::difference_with_serialized`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     rb1.difference_with_serialized_unchecked(Cursor::new(bytes)).unwrap(),
+    ///     rb1 - rb2,
+    /// );
+    /// ```
+    pub fn difference_with_serialized_unchecked<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        let other = read_serialized_unchecked(other, |key| self.find_container(key).is_some())?;
+        Ok(self - other)
+    }
+
+    /// Computes the symmetric difference between a materialized [`RoaringBitmap`]
+    /// and a serialized one.
+    ///
+    /// Every container of the serialized bitmap is read and checked like
+    /// [`RoaringBitmap::deserialize_from`] does. The serialized bitmap is read from
+    /// the current position of the reader, which is left at the end of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     rb1.symmetric_difference_with_serialized(Cursor::new(bytes)).unwrap(),
+    ///     rb1 ^ rb2,
+    /// );
+    /// ```
+    pub fn symmetric_difference_with_serialized<R>(&self, other: R) -> io::Result<RoaringBitmap>
+    where
+        R: io::Read + io::Seek,
+    {
+        let other = read_serialized_checked(other, |_| true)?;
+        Ok(self ^ other)
+    }
+
+    /// Computes the symmetric difference between a materialized [`RoaringBitmap`]
+    /// and a serialized one.
+    ///
+    /// This method is memory safe but will not check if the serialized data is a valid bitmap,
+    /// see [`RoaringBitmap::symmetric_difference_with_serialized`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(
+    ///     rb1.symmetric_difference_with_serialized_unchecked(Cursor::new(bytes)).unwrap(),
+    ///     rb1 ^ rb2,
+    /// );
+    /// ```
+    pub fn symmetric_difference_with_serialized_unchecked<R>(
         &self,
-        mut reader: R,
-        a: A,
-        b: B,
+        other: R,
     ) -> io::Result<RoaringBitmap>
     where
         R: io::Read + io::Seek,
-        A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
-        AErr: Error + Send + Sync + 'static,
-        B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
-        BErr: Error + Send + Sync + 'static,
     {
-        // First read the cookie to determine which version of the format we are reading
-        let (size, has_offsets, has_run_containers) = {
-            let cookie = reader.read_u32::<LittleEndian>()?;
-            if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
-                (reader.read_u32::<LittleEndian>()? as usize, true, false)
-            } else if (cookie as u16) == SERIAL_COOKIE {
-                let size = ((cookie >> 16) + 1) as usize;
-                (size, size >= NO_OFFSET_THRESHOLD, true)
-            } else {
-                return Err(io::Error::new(io::ErrorKind::Other, "unknown cookie value"));
-            }
-        };
+        let other = read_serialized_unchecked(other, |_| true)?;
+        Ok(self ^ other)
+    }
 
-        // Read the run container bitmap if necessary
-        let run_container_bitmap = if has_run_containers {
-            let mut bitmap = vec![0u8; (size + 7) / 8];
-            reader.read_exact(&mut bitmap)?;
-            Some(bitmap)
-        } else {
-            None
-        };
+    /// Computes the len of the intersection between a materialized [`RoaringBitmap`]
+    /// and a serialized one, without building the intersection.
+    ///
+    /// Only the serialized containers with a key present in this bitmap are read and checked
+    /// like [`RoaringBitmap::deserialize_from`] does. The serialized bitmap is read from
+    /// the current position of the reader, which is left at the end of it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.intersection_len_with_serialized(Cursor::new(bytes)).unwrap(), 1);
+    /// ```
+    pub fn intersection_len_with_serialized<R>(&self, other: R) -> io::Result<u64>
+    where
+        R: io::Read + io::Seek,
+    {
+        self.lens_with_serialized(other).map(|(_, intersection_len)| intersection_len)
+    }
 
-        if size > u16::MAX as usize + 1 {
-            return Err(io::Error::new(io::ErrorKind::Other, "size is greater than supported"));
-        }
+    /// Computes the len of the union between a materialized [`RoaringBitmap`]
+    /// and a serialized one, without building the union.
+    ///
+    /// The len of the serialized bitmap is known from its header, so only the
+    /// containers with a key present in this bitmap are read.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.union_len_with_serialized(Cursor::new(bytes)).unwrap(), 4);
+    /// ```
+    pub fn union_len_with_serialized<R>(&self, other: R) -> io::Result<u64>
+    where
+        R: io::Read + io::Seek,
+    {
+        let (other_len, intersection_len) = self.lens_with_serialized(other)?;
+        Ok(self.len() + other_len - intersection_len)
+    }
 
-        // Read the container descriptions
-        let mut descriptions = vec![[0; 2]; size];
-        reader.read_exact(cast_slice_mut(&mut descriptions))?;
-        descriptions.iter_mut().for_each(|[ref mut key, ref mut len]| {
-            *key = u16::from_le(*key);
-            *len = u16::from_le(*len);
-        });
-
-        if has_offsets {
-            let mut offsets = vec![0; size];
-            reader.read_exact(cast_slice_mut(&mut offsets))?;
-            offsets.iter_mut().for_each(|offset| *offset = u32::from_le(*offset));
-            return self.intersection_with_serialized_impl_with_offsets(
-                reader,
-                a,
-                b,
-                &descriptions,
-                &offsets,
-                run_container_bitmap.as_deref(),
-            );
-        }
+    /// Computes the len of the difference between a materialized [`RoaringBitmap`]
+    /// and a serialized one, without building the difference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.difference_len_with_serialized(Cursor::new(bytes)).unwrap(), 2);
+    /// ```
+    pub fn difference_len_with_serialized<R>(&self, other: R) -> io::Result<u64>
+    where
+        R: io::Read + io::Seek,
+    {
+        let (_, intersection_len) = self.lens_with_serialized(other)?;
+        Ok(self.len() - intersection_len)
+    }
 
-        // Read each container and skip the useless ones
-        let mut containers = Vec::new();
-        for (i, &[key, len_minus_one]) in descriptions.iter().enumerate() {
-            let container = match self.containers.binary_search_by_key(&key, |c| c.key) {
-                Ok(index) => self.containers.get(index),
-                Err(_) => None,
-            };
-            let cardinality = u64::from(len_minus_one) + 1;
-
-            // If the run container bitmap is present, check if this container is a run container
-            let is_run_container =
-                run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
-
-            let store = if is_run_container {
-                let runs = reader.read_u16::<LittleEndian>()?;
-                match container {
-                    Some(_) => {
-                        let mut intervals = vec![[0, 0]; runs as usize];
-                        reader.read_exact(cast_slice_mut(&mut intervals))?;
-                        intervals.iter_mut().for_each(|[s, len]| {
-                            *s = u16::from_le(*s);
-                            *len = u16::from_le(*len);
-                        });
-
-                        let cardinality = intervals.iter().map(|[_, len]| *len as usize).sum();
-                        let mut store = Store::with_capacity(cardinality);
-                        intervals.into_iter().try_for_each(
-                            |[s, len]| -> Result<(), io::ErrorKind> {
-                                let end = s.checked_add(len).ok_or(io::ErrorKind::InvalidData)?;
-                                store.insert_range(RangeInclusive::new(s, end));
-                                Ok(())
-                            },
-                        )?;
-                        store
-                    }
-                    None => {
-                        let runs_size = mem::size_of::<u16>() * 2 * runs as usize;
-                        reader.seek(SeekFrom::Current(runs_size as i64))?;
-                        continue;
-                    }
-                }
-            } else if cardinality <= ARRAY_LIMIT {
-                match container {
-                    Some(_) => {
-                        let mut values = vec![0; cardinality as usize];
-                        reader.read_exact(cast_slice_mut(&mut values))?;
-                        values.iter_mut().for_each(|n| *n = u16::from_le(*n));
-                        let array =
-                            a(values).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                        Store::Array(array)
-                    }
-                    None => {
-                        let array_size = mem::size_of::<u16>() * cardinality as usize;
-                        reader.seek(SeekFrom::Current(array_size as i64))?;
-                        continue;
-                    }
-                }
-            } else {
-                match container {
-                    Some(_) => {
-                        let mut values = Box::new([0; BITMAP_LENGTH]);
-                        reader.read_exact(cast_slice_mut(&mut values[..]))?;
-                        values.iter_mut().for_each(|n| *n = u64::from_le(*n));
-                        let bitmap = b(cardinality, values)
-                            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                        Store::Bitmap(bitmap)
-                    }
-                    None => {
-                        let bitmap_size = mem::size_of::<u64>() * BITMAP_LENGTH;
-                        reader.seek(SeekFrom::Current(bitmap_size as i64))?;
-                        continue;
-                    }
-                }
-            };
+    /// Computes the len of the symmetric difference between a materialized [`RoaringBitmap`]
+    /// and a serialized one, without building the symmetric difference.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    /// use std::io::Cursor;
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    ///
+    /// let mut bytes = Vec::new();
+    /// rb2.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(rb1.symmetric_difference_len_with_serialized(Cursor::new(bytes)).unwrap(), 3);
+    /// ```
+    pub fn symmetric_difference_len_with_serialized<R>(&self, other: R) -> io::Result<u64>
+    where
+        R: io::Read + io::Seek,
+    {
+        let (other_len, intersection_len) = self.lens_with_serialized(other)?;
+        Ok(self.len() + other_len - 2 * intersection_len)
+    }
 
-            if let Some(container) = container {
-                let mut other_container = Container { key, store };
-                other_container &= container;
-                if !other_container.is_empty() {
-                    containers.push(other_container);
+    /// Returns the len of the serialized bitmap and the len of its intersection with this one.
+    fn lens_with_serialized<R>(&self, reader: R) -> io::Result<(u64, u64)>
+    where
+        R: io::Read + io::Seek,
+    {
+        let mut intersection_len = 0;
+        let other_len = for_each_serialized_container(
+            reader,
+            ArrayStore::try_from,
+            BitmapStore::try_from,
+            IntervalStore::try_from_runs,
+            |key| self.find_container(key).is_some(),
+            |other| {
+                if let Some(container) = self.find_container(other.key) {
+                    intersection_len += container.intersection_len(&other);
                 }
-            }
-        }
-
-        Ok(RoaringBitmap { containers })
+            },
+        )?;
+        Ok((other_len, intersection_len))
     }
 
-    fn intersection_with_serialized_impl_with_offsets<R, A, AErr, B, BErr>(
+    fn intersection_with_serialized_impl<R, A, AErr, B, BErr, C, CErr>(
         &self,
-        mut reader: R,
+        reader: R,
         a: A,
         b: B,
-        descriptions: &[[u16; 2]],
-        offsets: &[u32],
-        run_container_bitmap: Option<&[u8]>,
+        c: C,
     ) -> io::Result<RoaringBitmap>
     where
         R: io::Read + io::Seek,
         A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
-        AErr: Error + Send + Sync + 'static,
+        AErr: StoreError,
         B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
-        BErr: Error + Send + Sync + 'static,
+        BErr: StoreError,
+        C: Fn(u64, Vec<Interval>) -> Result<IntervalStore, CErr>,
+        CErr: StoreError,
     {
         let mut containers = Vec::new();
-        for container in &self.containers {
-            let i = match descriptions.binary_search_by_key(&container.key, |[k, _]| *k) {
-                Ok(index) => index,
-                Err(_) => continue,
-            };
-
-            // Seek to the bytes of the container we want.
-            reader.seek(SeekFrom::Start(offsets[i] as u64))?;
-
-            let [key, len_minus_one] = descriptions[i];
-            let cardinality = u64::from(len_minus_one) + 1;
-
-            // If the run container bitmap is present, check if this container is a run container
-            let is_run_container =
-                run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
-
-            let store = if is_run_container {
-                let runs = reader.read_u16::<LittleEndian>().unwrap();
-                let mut intervals = vec![[0, 0]; runs as usize];
-                reader.read_exact(cast_slice_mut(&mut intervals)).unwrap();
-                intervals.iter_mut().for_each(|[s, len]| {
-                    *s = u16::from_le(*s);
-                    *len = u16::from_le(*len);
-                });
-
-                let cardinality = intervals.iter().map(|[_, len]| *len as usize).sum();
-                let mut store = Store::with_capacity(cardinality);
-                intervals.into_iter().try_for_each(|[s, len]| -> Result<(), io::ErrorKind> {
-                    let end = s.checked_add(len).ok_or(io::ErrorKind::InvalidData)?;
-                    store.insert_range(RangeInclusive::new(s, end));
-                    Ok(())
-                })?;
-                store
-            } else if cardinality <= ARRAY_LIMIT {
-                let mut values = vec![0; cardinality as usize];
-                reader.read_exact(cast_slice_mut(&mut values)).unwrap();
-                values.iter_mut().for_each(|n| *n = u16::from_le(*n));
-                let array = a(values).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                Store::Array(array)
-            } else {
-                let mut values = Box::new([0; BITMAP_LENGTH]);
-                reader.read_exact(cast_slice_mut(&mut values[..])).unwrap();
-                values.iter_mut().for_each(|n| *n = u64::from_le(*n));
-                let bitmap = b(cardinality, values)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                Store::Bitmap(bitmap)
-            };
-
-            let mut other_container = Container { key, store };
-            other_container &= container;
-            if !other_container.is_empty() {
-                containers.push(other_container);
+        for_each_serialized_container(
+            reader,
+            a,
+            b,
+            c,
+            |key| self.find_container(key).is_some(),
+            |mut other| {
+                if let Some(container) = self.find_container(other.key) {
+                    other &= container;
+                    if !other.is_empty() {
+                        containers.push(other);
+                    }
+                }
+            },
+        )?;
+        Ok(RoaringBitmap { containers })
+    }
+}
+
+/// Reads the containers of a serialized bitmap whose key is accepted by `wanted` into a bitmap.
+fn read_serialized<R, A, AErr, B, BErr, C, CErr>(
+    reader: R,
+    a: A,
+    b: B,
+    c: C,
+    wanted: impl Fn(u16) -> bool,
+) -> io::Result<RoaringBitmap>
+where
+    R: io::Read + io::Seek,
+    A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
+    AErr: StoreError,
+    B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
+    BErr: StoreError,
+    C: Fn(u64, Vec<Interval>) -> Result<IntervalStore, CErr>,
+    CErr: StoreError,
+{
+    let mut containers = Vec::new();
+    for_each_serialized_container(reader, a, b, c, wanted, |container| containers.push(container))?;
+    Ok(RoaringBitmap { containers })
+}
+
+fn read_serialized_checked<R>(reader: R, wanted: impl Fn(u16) -> bool) -> io::Result<RoaringBitmap>
+where
+    R: io::Read + io::Seek,
+{
+    read_serialized(
+        reader,
+        ArrayStore::try_from,
+        BitmapStore::try_from,
+        IntervalStore::try_from_runs,
+        wanted,
+    )
+}
+
+fn read_serialized_unchecked<R>(
+    reader: R,
+    wanted: impl Fn(u16) -> bool,
+) -> io::Result<RoaringBitmap>
+where
+    R: io::Read + io::Seek,
+{
+    read_serialized::<R, _, Infallible, _, Infallible, _, Infallible>(
+        reader,
+        |values| Ok(ArrayStore::from_vec_unchecked(values)),
+        |len, values| Ok(BitmapStore::from_unchecked(len, values)),
+        |_, intervals| Ok(run_store_unchecked(intervals)),
+        wanted,
+    )
+}
+
+/// Reads the containers of a serialized bitmap whose key is accepted by `wanted` and passes
+/// them to `f`, the other containers are skipped without being read.
+///
+/// Returns the len of the serialized bitmap as announced by its descriptions,
+/// the reader is left at the end of the serialized bitmap.
+fn for_each_serialized_container<R, A, AErr, B, BErr, C, CErr>(
+    mut reader: R,
+    a: A,
+    b: B,
+    c: C,
+    wanted: impl Fn(u16) -> bool,
+    mut f: impl FnMut(Container),
+) -> io::Result<u64>
+where
+    R: io::Read + io::Seek,
+    A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
+    AErr: StoreError,
+    B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
+    BErr: StoreError,
+    C: Fn(u64, Vec<Interval>) -> Result<IntervalStore, CErr>,
+    CErr: StoreError,
+{
+    // The offsets are relative to the start of the serialized bitmap
+    let start = reader.stream_position()?;
+
+    // First read the cookie to determine which version of the format we are reading
+    let (size, has_offsets, has_run_containers) = {
+        let cookie = reader.read_u32::<LittleEndian>()?;
+        if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+            (reader.read_u32::<LittleEndian>()? as usize, true, false)
+        } else if (cookie as u16) == SERIAL_COOKIE {
+            let size = ((cookie >> 16) + 1) as usize;
+            (size, size >= NO_OFFSET_THRESHOLD, true)
+        } else {
+            return Err(DeserializeError::UnknownCookie(cookie).into());
+        }
+    };
+
+    // Read the run container bitmap if necessary
+    let run_container_bitmap = if has_run_containers {
+        let mut bitmap = vec![0u8; (size + 7) / 8];
+        reader.read_exact(&mut bitmap)?;
+        Some(bitmap)
+    } else {
+        None
+    };
+
+    if size > u16::MAX as usize + 1 {
+        return Err(DeserializeError::TooManyContainers(size).into());
+    }
+
+    // Read the container descriptions
+    let mut descriptions = vec![[0; 2]; size];
+    reader.read_exact(cast_slice_mut(&mut descriptions))?;
+    descriptions.iter_mut().for_each(|[ref mut key, ref mut len]| {
+        *key = u16::from_le(*key);
+        *len = u16::from_le(*len);
+    });
+    if let Some(pair) = descriptions.windows(2).find(|pair| pair[0][0] >= pair[1][0]) {
+        return Err(DeserializeError::UnsortedKeys { key: pair[1][0] }.into());
+    }
+
+    // Read the offsets and make them absolute positions in the reader
+    let offsets: Option<Vec<u64>> = if has_offsets {
+        let mut offsets = vec![0u32; size];
+        reader.read_exact(cast_slice_mut(&mut offsets))?;
+        Some(offsets.into_iter().map(|offset| start + u64::from(u32::from_le(offset))).collect())
+    } else {
+        None
+    };
+
+    // If the run container bitmap is present, check if this container is a run container
+    let is_run_container = |i: usize| {
+        run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0)
+    };
+
+    // Read each wanted container, either by seeking to its offset
+    // or by skipping the useless ones that precede it
+    for (i, &[key, len_minus_one]) in descriptions.iter().enumerate() {
+        let cardinality = u64::from(len_minus_one) + 1;
+        if !wanted(key) {
+            if offsets.is_none() {
+                skip_store(&mut reader, is_run_container(i), cardinality)?;
             }
+            continue;
         }
+        if let Some(offsets) = &offsets {
+            reader.seek(SeekFrom::Start(offsets[i]))?;
+        }
+        let store = read_store(&mut reader, key, is_run_container(i), cardinality, &a, &b, &c)?;
+        f(Container { key, store });
+    }
 
-        Ok(RoaringBitmap { containers })
+    // Leave the reader at the end of the serialized bitmap, after the last container
+    if let (Some(offsets), Some(last)) = (&offsets, size.checked_sub(1)) {
+        reader.seek(SeekFrom::Start(offsets[last]))?;
+        skip_store(&mut reader, is_run_container(last), u64::from(descriptions[last][1]) + 1)?;
     }
+
+    Ok(descriptions.iter().map(|&[_, len_minus_one]| u64::from(len_minus_one) + 1).sum())
+}
+
+fn skip_store<R>(reader: &mut R, is_run_container: bool, cardinality: u64) -> io::Result<()>
+where
+    R: io::Read + io::Seek,
+{
+    let size = if is_run_container {
+        let runs = reader.read_u16::<LittleEndian>()?;
+        mem::size_of::<u16>() * 2 * runs as usize
+    } else if cardinality <= ARRAY_LIMIT {
+        mem::size_of::<u16>() * cardinality as usize
+    } else {
+        mem::size_of::<u64>() * BITMAP_LENGTH
+    };
+    reader.seek(SeekFrom::Current(size as i64))?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod test {
     use crate::RoaringBitmap;
     use proptest::prelude::*;
-    use std::io::Cursor;
+    use std::io::{Cursor, ErrorKind};
+
+    fn serialize(bitmap: &RoaringBitmap) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn intersection_with_serialized_rejects_invalid_containers() {
+        let everything = RoaringBitmap::from_iter(0..0x10000);
+
+        // Array of [1, 2, 3] after the 16 bytes of the header, with a duplicated value
+        let mut bytes = serialize(&RoaringBitmap::from([1, 2, 3]));
+        bytes[16] = 2;
+        let error = everything.intersection_with_serialized(Cursor::new(&bytes)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // Bitset of 5000 values with one more bit set than its cardinality
+        let mut bytes = serialize(&RoaringBitmap::from_iter(0..5000));
+        bytes[16 + 5000 / 8] |= 0x80;
+        let error = everything.intersection_with_serialized(Cursor::new(&bytes)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+        // Runs [0, 9] and [20, 29] after the cookie, the run bitmap, the description
+        // and the number of runs: make the second one overlap the first one
+        let mut bitmap = RoaringBitmap::from_iter((0..10).chain(20..30));
+        bitmap.optimize();
+        let mut bytes = serialize(&bitmap);
+        bytes[15] = 5;
+        let error = everything.intersection_with_serialized(Cursor::new(&bytes)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(everything.intersection_with_serialized_unchecked(Cursor::new(&bytes)).is_ok());
+
+        // A run going past the end of the container
+        let mut bytes = serialize(&bitmap);
+        bytes[17] = 0xFF;
+        bytes[18] = 0xFF;
+        let error = everything.intersection_with_serialized(Cursor::new(&bytes)).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
 
     // fast count tests
     proptest! {
@@ -294,7 +682,62 @@ mod test {
             b.serialize_into(&mut serialized_bytes_b).unwrap();
             let serialized_bytes_b = &serialized_bytes_b[..];
 
+            prop_assert_eq!(a.intersection_with_serialized(Cursor::new(serialized_bytes_b)).unwrap(), &a & &b);
             prop_assert_eq!(a.intersection_with_serialized_unchecked(Cursor::new(serialized_bytes_b)).unwrap(), a & b);
         }
+
+        #[test]
+        fn ops_with_serialized_eq_materialized_ops(
+            a in RoaringBitmap::arbitrary(),
+            mut b in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+        ) {
+            if optimize {
+                b.optimize();
+            }
+            let mut bytes = Vec::new();
+            b.serialize_into(&mut bytes).unwrap();
+            let b_len = bytes.len() as u64;
+            // The trailing bytes check that the reader is left at the end of the bitmap
+            bytes.extend_from_slice(&[1, 2, 3]);
+            let serialized = || Cursor::new(&bytes[..]);
+
+            let union = &a | &b;
+            let difference = &a - &b;
+            let symmetric_difference = &a ^ &b;
+
+            let mut reader = serialized();
+            prop_assert_eq!(&a.union_with_serialized(&mut reader).unwrap(), &union);
+            prop_assert_eq!(reader.position(), b_len);
+            let mut reader = serialized();
+            prop_assert_eq!(&a.difference_with_serialized(&mut reader).unwrap(), &difference);
+            prop_assert_eq!(reader.position(), b_len);
+            let mut reader = serialized();
+            prop_assert_eq!(
+                &a.symmetric_difference_with_serialized(&mut reader).unwrap(),
+                &symmetric_difference
+            );
+            prop_assert_eq!(reader.position(), b_len);
+
+            prop_assert_eq!(&a.union_with_serialized_unchecked(serialized()).unwrap(), &union);
+            prop_assert_eq!(
+                &a.difference_with_serialized_unchecked(serialized()).unwrap(),
+                &difference
+            );
+            prop_assert_eq!(
+                &a.symmetric_difference_with_serialized_unchecked(serialized()).unwrap(),
+                &symmetric_difference
+            );
+
+            let mut reader = serialized();
+            prop_assert_eq!(a.intersection_len_with_serialized(&mut reader).unwrap(), a.intersection_len(&b));
+            prop_assert_eq!(reader.position(), b_len);
+            prop_assert_eq!(a.union_len_with_serialized(serialized()).unwrap(), union.len());
+            prop_assert_eq!(a.difference_len_with_serialized(serialized()).unwrap(), difference.len());
+            prop_assert_eq!(
+                a.symmetric_difference_len_with_serialized(serialized()).unwrap(),
+                symmetric_difference.len()
+            );
+        }
     }
 }
@@ -0,0 +1,154 @@
+use crate::RoaringBitmap;
+
+use super::store::{Store, BITMAP_LENGTH};
+use super::util;
+
+#[cfg(not(feature = "std"))]
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// An index speeding up the rank and select operations of a bitmap that is not mutated
+/// anymore, see [`RoaringBitmap::rank_index`].
+///
+/// It stores the cumulative cardinality of the containers and, for the bitset containers,
+/// the number of values before each of their 64-bit words. The index borrows the bitmap,
+/// it must be rebuilt once the bitmap has been mutated.
+#[derive(Clone)]
+pub struct RankIndex<'a> {
+    bitmap: &'a RoaringBitmap,
+    // The number of values before each container, followed by the length of the bitmap
+    cumulative: Vec<u64>,
+    // The word prefixes of the bitset containers, `None` for the other stores
+    prefixes: Vec<Option<Box<[u16; BITMAP_LENGTH]>>>,
+}
+
+impl<'a> RankIndex<'a> {
+    /// Builds the index of the bitmap.
+    pub fn new(bitmap: &'a RoaringBitmap) -> RankIndex<'a> {
+        let mut cumulative = Vec::with_capacity(bitmap.containers.len() + 1);
+        let mut prefixes = Vec::with_capacity(bitmap.containers.len());
+        let mut len = 0;
+        for container in &bitmap.containers {
+            cumulative.push(len);
+            len += container.len();
+            prefixes.push(match &container.store {
+                Store::Bitmap(bits) => Some(bits.word_prefixes()),
+                Store::Array(_) | Store::Run(_) => None,
+            });
+        }
+        cumulative.push(len);
+        RankIndex { bitmap, cumulative, prefixes }
+    }
+
+    /// Returns the indexed bitmap.
+    pub fn bitmap(&self) -> &'a RoaringBitmap {
+        self.bitmap
+    }
+
+    /// Returns the number of integers that are <= value, same as [`RoaringBitmap::rank`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(100_000..110_000).collect();
+    /// let index = rb.rank_index();
+    ///
+    /// assert_eq!(index.rank(5), 6);
+    /// assert_eq!(index.rank(105_000), 5011);
+    /// assert_eq!(index.rank(u32::MAX), 10_010);
+    /// ```
+    pub fn rank(&self, value: u32) -> u64 {
+        let (key, index) = util::split(value);
+        let containers = &self.bitmap.containers;
+        match containers.binary_search_by_key(&key, |c| c.key) {
+            Ok(i) => {
+                let rank = match (&containers[i].store, &self.prefixes[i]) {
+                    (Store::Bitmap(bits), Some(prefixes)) => {
+                        bits.rank_with_prefixes(prefixes, index)
+                    }
+                    (store, _) => store.rank(index),
+                };
+                self.cumulative[i] + rank
+            }
+            Err(i) => self.cumulative[i],
+        }
+    }
+
+    /// Returns the `n`th integer in the set or `None` if `n >= len()`,
+    /// same as [`RoaringBitmap::select`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (0..10).chain(100_000..110_000).collect();
+    /// let index = rb.rank_index();
+    ///
+    /// assert_eq!(index.select(5), Some(5));
+    /// assert_eq!(index.select(5010), Some(105_000));
+    /// assert_eq!(index.select(10_010), None);
+    /// ```
+    pub fn select(&self, n: u32) -> Option<u32> {
+        let n = u64::from(n);
+        // The first cumulative cardinality is always zero, there is at least one <= n
+        let i = self.cumulative.partition_point(|&cumulative| cumulative <= n) - 1;
+        let container = self.bitmap.containers.get(i)?;
+        let n = (n - self.cumulative[i]) as u16;
+        let index = match (&container.store, &self.prefixes[i]) {
+            (Store::Bitmap(bits), Some(prefixes)) => bits.select_with_prefixes(prefixes, n),
+            (store, _) => store.select(n),
+        }?;
+        Some(util::join(container.key, index))
+    }
+}
+
+impl RoaringBitmap {
+    /// Builds an index speeding up the rank and select operations on this bitmap.
+    ///
+    /// The index borrows the bitmap, it must be rebuilt after the bitmap has been mutated.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let mut rb: RoaringBitmap = (0..100_000).step_by(3).collect();
+    /// let index = rb.rank_index();
+    /// assert_eq!(index.rank(30), 11);
+    /// assert_eq!(index.select(10), Some(30));
+    ///
+    /// rb.insert(1);
+    /// assert_eq!(rb.rank_index().rank(30), 12);
+    /// ```
+    pub fn rank_index(&self) -> RankIndex<'_> {
+        RankIndex::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::RoaringBitmap;
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn rank_and_select_eq_bitmap(
+            bitmap in RoaringBitmap::arbitrary(),
+            values in vec(0u32..0x12_0000, ..=100),
+            ranks in vec(0u32..0x3_0000, ..=100),
+        ) {
+            let index = bitmap.rank_index();
+            for value in values {
+                prop_assert_eq!(index.rank(value), bitmap.rank(value));
+            }
+            for n in ranks {
+                prop_assert_eq!(index.select(n), bitmap.select(n));
+            }
+        }
+    }
+}
@@ -1,11 +1,13 @@
 use crate::bitmap::container::{Container, ARRAY_LIMIT};
-use crate::bitmap::store::{ArrayStore, BitmapStore, Store, BITMAP_LENGTH};
+use crate::bitmap::store::{
+    ArrayStore, BitmapStore, Interval, IntervalStore, Store, BITMAP_LENGTH,
+};
 use crate::RoaringBitmap;
 use bytemuck::cast_slice_mut;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use core::convert::Infallible;
+use core::fmt;
 use core::mem::size_of;
-use core::ops::RangeInclusive;
 use std::error::Error;
 use std::io;
 
@@ -17,6 +19,129 @@ pub const NO_OFFSET_THRESHOLD: usize = 4;
 pub const DESCRIPTION_BYTES: usize = 4;
 pub const OFFSET_BYTES: usize = 4;
 
+// The cookie and container typecodes of the CRoaring frozen format
+pub const FROZEN_COOKIE: u32 = 13766;
+pub const FROZEN_BITSET_TYPE: u8 = 1;
+pub const FROZEN_ARRAY_TYPE: u8 = 2;
+pub const FROZEN_RUN_TYPE: u8 = 3;
+
+/// An error that occurred while deserializing a [`RoaringBitmap`].
+///
+/// It can be converted into an [`io::Error`] of kind [`io::ErrorKind::InvalidData`],
+/// or into the underlying [`io::Error`] for [`DeserializeError::Io`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DeserializeError {
+    /// The bytes do not start with a known cookie
+    UnknownCookie(u32),
+    /// The header announces more than the 65536 containers a bitmap can hold
+    TooManyContainers(usize),
+    /// The key of a container is not greater than the key of the previous one
+    UnsortedKeys {
+        /// The key of the container
+        key: u16,
+    },
+    /// The values of an array container are not strictly increasing
+    UnsortedArray {
+        /// The key of the container
+        key: u16,
+    },
+    /// The number of values in a container does not match its description
+    CardinalityMismatch {
+        /// The key of the container
+        key: u16,
+        /// The cardinality announced by the description
+        expected: u64,
+        /// The number of values in the container
+        actual: u64,
+    },
+    /// The runs of a run container overflow, are not sorted, overlap or are adjacent
+    InvalidRun {
+        /// The key of the container
+        key: u16,
+    },
+    /// The type of a container of a frozen bitmap is unknown
+    InvalidTypecode {
+        /// The key of the container
+        key: u16,
+        /// The unknown typecode
+        typecode: u8,
+    },
+    /// The containers of a frozen bitmap do not fill the bytes before its header
+    SizeMismatch {
+        /// The number of bytes taken by the containers
+        expected: usize,
+        /// The number of bytes before the header
+        actual: usize,
+    },
+    /// The reader failed, for example because the bytes are truncated
+    Io(io::Error),
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeserializeError::UnknownCookie(cookie) => write!(f, "unknown cookie value {cookie}"),
+            DeserializeError::TooManyContainers(size) => {
+                write!(f, "{size} containers is greater than supported")
+            }
+            DeserializeError::UnsortedKeys { key } => {
+                write!(f, "container keys are not strictly increasing at key {key}")
+            }
+            DeserializeError::UnsortedArray { key } => {
+                write!(f, "array container {key} is not strictly increasing")
+            }
+            DeserializeError::CardinalityMismatch { key, expected, actual } => {
+                write!(f, "container {key} expected cardinality {expected} but had {actual}")
+            }
+            DeserializeError::InvalidRun { key } => write!(f, "run container {key} is invalid"),
+            DeserializeError::InvalidTypecode { key, typecode } => {
+                write!(f, "container {key} has the unknown typecode {typecode}")
+            }
+            DeserializeError::SizeMismatch { expected, actual } => {
+                write!(f, "containers take {expected} bytes but {actual} bytes were found")
+            }
+            DeserializeError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for DeserializeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DeserializeError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DeserializeError {
+    fn from(error: io::Error) -> Self {
+        DeserializeError::Io(error)
+    }
+}
+
+impl From<DeserializeError> for io::Error {
+    fn from(error: DeserializeError) -> Self {
+        match error {
+            DeserializeError::Io(error) => error,
+            error => io::Error::new(io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+/// An error of a store constructor, which becomes a [`DeserializeError`]
+/// once the key of the container is known.
+pub(crate) trait StoreError {
+    fn with_key(self, key: u16) -> DeserializeError;
+}
+
+impl StoreError for Infallible {
+    fn with_key(self, _key: u16) -> DeserializeError {
+        match self {}
+    }
+}
+
 impl RoaringBitmap {
     /// Return the size in bytes of the serialized output.
     /// This is compatible with the official C/C++, Java and Go implementations.
@@ -37,14 +162,15 @@ impl RoaringBitmap {
         let container_sizes: usize = self
             .containers
             .iter()
-            .map(|container| match container.store {
-                Store::Array(ref values) => 8 + values.len() as usize * 2,
-                Store::Bitmap(..) => 8 + 8 * 1024,
-            })
+            .map(|container| container_serialized_size(&container.store))
             .sum();
 
         // header + container sizes
-        8 + container_sizes
+        header_size(self.containers.len(), self.has_run_containers()) + container_sizes
+    }
+
+    fn has_run_containers(&self) -> bool {
+        self.containers.iter().any(|container| matches!(container.store, Store::Run(_)))
     }
 
     /// Creates a `RoaringBitmap` from a byte slice, interpreting the bytes as a bitmap with a specified offset.
@@ -52,7 +178,7 @@ impl RoaringBitmap {
     /// # Arguments
     ///
     /// - `offset: u32` - The starting position in the bitmap where the byte slice will be applied, specified in bits.
-    ///                   This means that if `offset` is `n`, the first byte in the slice will correspond to the `n`th bit(0-indexed) in the bitmap.
+    ///   This means that if `offset` is `n`, the first byte in the slice will correspond to the `n`th bit(0-indexed) in the bitmap.
     /// - `bytes: &[u8]` - The byte slice containing the bitmap data. The bytes are interpreted in "Least-Significant-First" bit order.
     ///
     /// # Interpretation of `bytes`
@@ -198,24 +324,35 @@ impl RoaringBitmap {
     /// assert_eq!(rb1, rb2);
     /// ```
     pub fn serialize_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
-        writer.write_u32::<LittleEndian>(SERIAL_COOKIE_NO_RUNCONTAINER)?;
-        writer.write_u32::<LittleEndian>(self.containers.len() as u32)?;
+        let size = self.containers.len();
+        let has_run_containers = self.has_run_containers();
+
+        if has_run_containers {
+            writer
+                .write_u32::<LittleEndian>(u32::from(SERIAL_COOKIE) | ((size as u32 - 1) << 16))?;
+            let mut run_container_bitmap = vec![0u8; (size + 7) / 8];
+            for (i, container) in self.containers.iter().enumerate() {
+                if let Store::Run(_) = container.store {
+                    run_container_bitmap[i / 8] |= 1 << (i % 8);
+                }
+            }
+            writer.write_all(&run_container_bitmap)?;
+        } else {
+            writer.write_u32::<LittleEndian>(SERIAL_COOKIE_NO_RUNCONTAINER)?;
+            writer.write_u32::<LittleEndian>(size as u32)?;
+        }
 
         for container in &self.containers {
             writer.write_u16::<LittleEndian>(container.key)?;
             writer.write_u16::<LittleEndian>((container.len() - 1) as u16)?;
         }
 
-        let mut offset = 8 + 8 * self.containers.len() as u32;
-        for container in &self.containers {
-            writer.write_u32::<LittleEndian>(offset)?;
-            match container.store {
-                Store::Array(ref values) => {
-                    offset += values.len() as u32 * 2;
-                }
-                Store::Bitmap(..) => {
-                    offset += 8 * 1024;
-                }
+        // The offsets are omitted for small bitmaps with run containers
+        if !has_run_containers || size >= NO_OFFSET_THRESHOLD {
+            let mut offset = header_size(size, has_run_containers) as u32;
+            for container in &self.containers {
+                writer.write_u32::<LittleEndian>(offset)?;
+                offset += container_serialized_size(&container.store) as u32;
             }
         }
 
@@ -231,16 +368,121 @@ impl RoaringBitmap {
                         writer.write_u64::<LittleEndian>(value)?;
                     }
                 }
+                Store::Run(ref runs) => {
+                    writer.write_u16::<LittleEndian>(runs.run_amount() as u16)?;
+                    for interval in runs.as_slice() {
+                        writer.write_u16::<LittleEndian>(interval.start())?;
+                        writer.write_u16::<LittleEndian>(interval.end() - interval.start())?;
+                    }
+                }
             }
         }
 
         Ok(())
     }
 
+    /// Return the size in bytes of the output of [`RoaringBitmap::serialize_frozen_into`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = Vec::with_capacity(rb.frozen_size());
+    /// rb.serialize_frozen_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(bytes.len(), rb.frozen_size());
+    /// ```
+    pub fn frozen_size(&self) -> usize {
+        let container_sizes: usize = self
+            .containers
+            .iter()
+            .map(|container| match container.store {
+                Store::Array(ref values) => ArrayStore::serialized_byte_size(values.len()),
+                Store::Bitmap(..) => BitmapStore::serialized_byte_size(),
+                Store::Run(ref runs) => runs.run_amount() * 4,
+            })
+            .sum();
+
+        // keys, counts and typecodes of the containers then the header
+        container_sizes + 5 * self.containers.len() + 4
+    }
+
+    /// Serialize this bitmap into the CRoaring frozen format, as written by
+    /// `roaring_bitmap_frozen_serialize`.
+    ///
+    /// The frozen format stores the containers grouped by type and the header at the end
+    /// of the buffer, it is meant to be read in place with [`RoaringBitmapView::frozen`].
+    ///
+    /// [`RoaringBitmapView::frozen`]: crate::RoaringBitmapView::frozen
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_frozen_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::frozen(&bytes).unwrap();
+    ///
+    /// assert!(view.iter().eq(rb.iter()));
+    /// ```
+    pub fn serialize_frozen_into<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        for container in &self.containers {
+            if let Store::Bitmap(ref bits) = container.store {
+                for &value in bits.as_array() {
+                    writer.write_u64::<LittleEndian>(value)?;
+                }
+            }
+        }
+
+        for container in &self.containers {
+            if let Store::Run(ref runs) = container.store {
+                for interval in runs.as_slice() {
+                    writer.write_u16::<LittleEndian>(interval.start())?;
+                    writer.write_u16::<LittleEndian>(interval.end() - interval.start())?;
+                }
+            }
+        }
+
+        for container in &self.containers {
+            if let Store::Array(ref values) = container.store {
+                for &value in values.iter() {
+                    writer.write_u16::<LittleEndian>(value)?;
+                }
+            }
+        }
+
+        for container in &self.containers {
+            writer.write_u16::<LittleEndian>(container.key)?;
+        }
+
+        for container in &self.containers {
+            let count = match container.store {
+                Store::Run(ref runs) => runs.run_amount() as u64,
+                _ => container.len() - 1,
+            };
+            writer.write_u16::<LittleEndian>(count as u16)?;
+        }
+
+        for container in &self.containers {
+            writer.write_u8(match container.store {
+                Store::Bitmap(..) => FROZEN_BITSET_TYPE,
+                Store::Array(..) => FROZEN_ARRAY_TYPE,
+                Store::Run(..) => FROZEN_RUN_TYPE,
+            })?;
+        }
+
+        writer.write_u32::<LittleEndian>(((self.containers.len() as u32) << 15) | FROZEN_COOKIE)
+    }
+
     /// Deserialize a bitmap into memory from [the standard Roaring on-disk
     /// format][format]. This is compatible with the official C/C++, Java and
     /// Go implementations. This method checks that all of the internal values
-    /// are valid. If deserializing from a trusted source consider
+    /// are valid and returns a [`DeserializeError`] describing the first invalid one.
+    /// If deserializing from a trusted source consider
     /// [RoaringBitmap::deserialize_unchecked_from]
     ///
     /// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
@@ -257,8 +499,13 @@ impl RoaringBitmap {
     ///
     /// assert_eq!(rb1, rb2);
     /// ```
-    pub fn deserialize_from<R: io::Read>(reader: R) -> io::Result<RoaringBitmap> {
-        RoaringBitmap::deserialize_from_impl(reader, ArrayStore::try_from, BitmapStore::try_from)
+    pub fn deserialize_from<R: io::Read>(reader: R) -> Result<RoaringBitmap, DeserializeError> {
+        RoaringBitmap::deserialize_from_impl(
+            reader,
+            ArrayStore::try_from,
+            BitmapStore::try_from,
+            IntervalStore::try_from_runs,
+        )
     }
 
     /// Deserialize a bitmap into memory from [the standard Roaring on-disk
@@ -280,25 +527,31 @@ impl RoaringBitmap {
     ///
     /// assert_eq!(rb1, rb2);
     /// ```
-    pub fn deserialize_unchecked_from<R: io::Read>(reader: R) -> io::Result<RoaringBitmap> {
-        RoaringBitmap::deserialize_from_impl::<R, _, Infallible, _, Infallible>(
+    pub fn deserialize_unchecked_from<R: io::Read>(
+        reader: R,
+    ) -> Result<RoaringBitmap, DeserializeError> {
+        RoaringBitmap::deserialize_from_impl::<R, _, Infallible, _, Infallible, _, Infallible>(
             reader,
             |values| Ok(ArrayStore::from_vec_unchecked(values)),
             |len, values| Ok(BitmapStore::from_unchecked(len, values)),
+            |_, intervals| Ok(run_store_unchecked(intervals)),
         )
     }
 
-    fn deserialize_from_impl<R, A, AErr, B, BErr>(
+    fn deserialize_from_impl<R, A, AErr, B, BErr, C, CErr>(
         mut reader: R,
         a: A,
         b: B,
-    ) -> io::Result<RoaringBitmap>
+        c: C,
+    ) -> Result<RoaringBitmap, DeserializeError>
     where
         R: io::Read,
         A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
-        AErr: Error + Send + Sync + 'static,
+        AErr: StoreError,
         B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
-        BErr: Error + Send + Sync + 'static,
+        BErr: StoreError,
+        C: Fn(u64, Vec<Interval>) -> Result<IntervalStore, CErr>,
+        CErr: StoreError,
     {
         // First read the cookie to determine which version of the format we are reading
         let (size, has_offsets, has_run_containers) = {
@@ -309,7 +562,7 @@ impl RoaringBitmap {
                 let size = ((cookie >> 16) + 1) as usize;
                 (size, size >= NO_OFFSET_THRESHOLD, true)
             } else {
-                return Err(io::Error::new(io::ErrorKind::Other, "unknown cookie value"));
+                return Err(DeserializeError::UnknownCookie(cookie));
             }
         };
 
@@ -323,7 +576,7 @@ impl RoaringBitmap {
         };
 
         if size > u16::MAX as usize + 1 {
-            return Err(io::Error::new(io::ErrorKind::Other, "size is greater than supported"));
+            return Err(DeserializeError::TooManyContainers(size));
         }
 
         // Read the container descriptions
@@ -344,42 +597,16 @@ impl RoaringBitmap {
             let key = description_bytes.read_u16::<LittleEndian>()?;
             let cardinality = u64::from(description_bytes.read_u16::<LittleEndian>()?) + 1;
 
+            // The containers must be sorted by key for the bitmap to find them
+            if containers.last().map_or(false, |last: &Container| last.key >= key) {
+                return Err(DeserializeError::UnsortedKeys { key });
+            }
+
             // If the run container bitmap is present, check if this container is a run container
             let is_run_container =
                 run_container_bitmap.as_ref().map_or(false, |bm| bm[i / 8] & (1 << (i % 8)) != 0);
 
-            let store = if is_run_container {
-                let runs = reader.read_u16::<LittleEndian>()?;
-                let mut intervals = vec![[0, 0]; runs as usize];
-                reader.read_exact(cast_slice_mut(&mut intervals))?;
-                intervals.iter_mut().for_each(|[s, len]| {
-                    *s = u16::from_le(*s);
-                    *len = u16::from_le(*len);
-                });
-
-                let cardinality = intervals.iter().map(|[_, len]| *len as usize).sum();
-                let mut store = Store::with_capacity(cardinality);
-                intervals.into_iter().try_for_each(|[s, len]| -> Result<(), io::ErrorKind> {
-                    let end = s.checked_add(len).ok_or(io::ErrorKind::InvalidData)?;
-                    store.insert_range(RangeInclusive::new(s, end));
-                    Ok(())
-                })?;
-                store
-            } else if cardinality <= ARRAY_LIMIT {
-                let mut values = vec![0; cardinality as usize];
-                reader.read_exact(cast_slice_mut(&mut values))?;
-                values.iter_mut().for_each(|n| *n = u16::from_le(*n));
-                let array = a(values).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                Store::Array(array)
-            } else {
-                let mut values = Box::new([0; BITMAP_LENGTH]);
-                reader.read_exact(cast_slice_mut(&mut values[..]))?;
-                values.iter_mut().for_each(|n| *n = u64::from_le(*n));
-                let bitmap = b(cardinality, values)
-                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
-                Store::Bitmap(bitmap)
-            };
-
+            let store = read_store(&mut reader, key, is_run_container, cardinality, &a, &b, &c)?;
             containers.push(Container { key, store });
         }
 
@@ -387,10 +614,95 @@ impl RoaringBitmap {
     }
 }
 
+/// Reads the payload of the container of `key`, building its store with `a`, `b` or `c`.
+pub(crate) fn read_store<R, A, AErr, B, BErr, C, CErr>(
+    reader: &mut R,
+    key: u16,
+    is_run_container: bool,
+    cardinality: u64,
+    a: &A,
+    b: &B,
+    c: &C,
+) -> Result<Store, DeserializeError>
+where
+    R: io::Read,
+    A: Fn(Vec<u16>) -> Result<ArrayStore, AErr>,
+    AErr: StoreError,
+    B: Fn(u64, Box<[u64; 1024]>) -> Result<BitmapStore, BErr>,
+    BErr: StoreError,
+    C: Fn(u64, Vec<Interval>) -> Result<IntervalStore, CErr>,
+    CErr: StoreError,
+{
+    if is_run_container {
+        let runs = reader.read_u16::<LittleEndian>()?;
+        let mut intervals = vec![[0, 0]; runs as usize];
+        reader.read_exact(cast_slice_mut(&mut intervals))?;
+        intervals.iter_mut().for_each(|[s, len]| {
+            *s = u16::from_le(*s);
+            *len = u16::from_le(*len);
+        });
+
+        let intervals = intervals
+            .into_iter()
+            .map(|[s, len]| {
+                let end = s.checked_add(len).ok_or(DeserializeError::InvalidRun { key })?;
+                Ok(Interval::new(s, end))
+            })
+            .collect::<Result<Vec<_>, DeserializeError>>()?;
+        let store = c(cardinality, intervals).map_err(|e| e.with_key(key))?;
+        Ok(Store::Run(store))
+    } else if cardinality <= ARRAY_LIMIT {
+        let mut values = vec![0; cardinality as usize];
+        reader.read_exact(cast_slice_mut(&mut values))?;
+        values.iter_mut().for_each(|n| *n = u16::from_le(*n));
+        let array = a(values).map_err(|e| e.with_key(key))?;
+        Ok(Store::Array(array))
+    } else {
+        let mut values = Box::new([0; BITMAP_LENGTH]);
+        reader.read_exact(cast_slice_mut(&mut values[..]))?;
+        values.iter_mut().for_each(|n| *n = u64::from_le(*n));
+        let bitmap = b(cardinality, values).map_err(|e| e.with_key(key))?;
+        Ok(Store::Bitmap(bitmap))
+    }
+}
+
+/// Builds a run store from intervals that may be unsorted or overlapping.
+pub(crate) fn run_store_unchecked(intervals: Vec<Interval>) -> IntervalStore {
+    let mut store = IntervalStore::new();
+    for interval in intervals {
+        store.insert_range(interval.range());
+    }
+    store
+}
+
+/// The size of the cookie, the descriptions and the offsets of a serialized bitmap.
+fn header_size(size: usize, has_run_containers: bool) -> usize {
+    if has_run_containers {
+        let run_container_bitmap_bytes = (size + 7) / 8;
+        if size < NO_OFFSET_THRESHOLD {
+            4 + run_container_bitmap_bytes + DESCRIPTION_BYTES * size
+        } else {
+            4 + run_container_bitmap_bytes + (DESCRIPTION_BYTES + OFFSET_BYTES) * size
+        }
+    } else {
+        8 + (DESCRIPTION_BYTES + OFFSET_BYTES) * size
+    }
+}
+
+fn container_serialized_size(store: &Store) -> usize {
+    match store {
+        Store::Array(values) => ArrayStore::serialized_byte_size(values.len()),
+        Store::Bitmap(..) => BitmapStore::serialized_byte_size(),
+        Store::Run(runs) => runs.byte_size(),
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use super::DeserializeError;
     use crate::{bitmap::store::BITMAP_LENGTH, RoaringBitmap};
     use proptest::prelude::*;
+    use std::io::{self, ErrorKind};
 
     proptest! {
         #[test]
@@ -401,6 +713,17 @@ mod test {
             bitmap.serialize_into(&mut buffer).unwrap();
             prop_assert_eq!(bitmap, RoaringBitmap::deserialize_from(buffer.as_slice()).unwrap());
         }
+
+        #[test]
+        fn test_serialization_with_runs(
+            mut bitmap in RoaringBitmap::arbitrary(),
+        ) {
+            bitmap.optimize();
+            let mut buffer = Vec::new();
+            bitmap.serialize_into(&mut buffer).unwrap();
+            prop_assert_eq!(buffer.len(), bitmap.serialized_size());
+            prop_assert_eq!(bitmap, RoaringBitmap::deserialize_from(buffer.as_slice()).unwrap());
+        }
     }
 
     #[test]
@@ -471,6 +794,27 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_serialize_frozen() {
+        let mut bitmap: RoaringBitmap = (1..4).chain(65536..65636).collect();
+        bitmap.optimize();
+
+        let mut buffer = Vec::new();
+        bitmap.serialize_frozen_into(&mut buffer).unwrap();
+
+        #[rustfmt::skip]
+        let expected = [
+            0x00, 0x00, 0x63, 0x00, // run zone
+            0x01, 0x00, 0x02, 0x00, 0x03, 0x00, // array zone
+            0x00, 0x00, 0x01, 0x00, // keys
+            0x02, 0x00, 0x01, 0x00, // counts
+            0x02, 0x03, // typecodes
+            0xC6, 0x35, 0x01, 0x00, // header
+        ];
+        assert_eq!(buffer, expected);
+        assert_eq!(buffer.len(), bitmap.frozen_size());
+    }
+
     #[test]
     #[should_panic(expected = "<= 2^32")]
     fn test_from_lsb0_bytes_overflow() {
@@ -484,4 +828,68 @@ mod test {
         let res = RoaringBitmap::deserialize_from(data.as_slice());
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_deserialize_unsorted_keys() {
+        let bitmap: RoaringBitmap = [1, 65536 + 1].into_iter().collect();
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        // Give the second container the key of the first one
+        bytes[12] = 0;
+
+        let error = RoaringBitmap::deserialize_from(&bytes[..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::UnsortedKeys { key: 0 }));
+        let error = RoaringBitmap::deserialize_unchecked_from(&bytes[..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::UnsortedKeys { key: 0 }));
+    }
+
+    #[test]
+    fn test_deserialize_invalid_runs() {
+        let mut bitmap: RoaringBitmap = (1..4).chain(10..20).collect();
+        bitmap.optimize();
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+
+        // Make the second run start inside the first one
+        let mut overlapping = bytes.clone();
+        overlapping[15] = 3;
+        let error = RoaringBitmap::deserialize_from(&overlapping[..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::InvalidRun { key: 0 }));
+        let unchecked = RoaringBitmap::deserialize_unchecked_from(&overlapping[..]).unwrap();
+        assert_eq!(unchecked, (1..13).collect());
+
+        // Announce one more value than the runs contain
+        let mut cardinality = bytes;
+        cardinality[7] += 1;
+        let error = RoaringBitmap::deserialize_from(&cardinality[..]).unwrap_err();
+        assert!(matches!(
+            error,
+            DeserializeError::CardinalityMismatch { key: 0, expected: 14, actual: 13 }
+        ));
+    }
+
+    #[test]
+    fn test_deserialize_errors() {
+        let mut bytes = Vec::new();
+        RoaringBitmap::from_iter(1..4).serialize_into(&mut bytes).unwrap();
+
+        let error = RoaringBitmap::deserialize_from(&[0, 0, 0, 0][..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::UnknownCookie(0)));
+
+        let mut too_many = bytes.clone();
+        too_many[4..8].copy_from_slice(&(u16::MAX as u32 + 2).to_le_bytes());
+        let error = RoaringBitmap::deserialize_from(&too_many[..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::TooManyContainers(65537)));
+
+        // Replace the value 2 by 1
+        let mut unsorted = bytes.clone();
+        unsorted[18] = 1;
+        let error = RoaringBitmap::deserialize_from(&unsorted[..]).unwrap_err();
+        assert!(matches!(error, DeserializeError::UnsortedArray { key: 0 }));
+        assert_eq!(io::Error::from(error).kind(), ErrorKind::InvalidData);
+
+        let error = RoaringBitmap::deserialize_from(&bytes[..bytes.len() - 1]).unwrap_err();
+        assert!(matches!(&error, DeserializeError::Io(e) if e.kind() == ErrorKind::UnexpectedEof));
+        assert_eq!(io::Error::from(error).kind(), ErrorKind::UnexpectedEof);
+    }
 }
@@ -16,6 +16,7 @@ use alloc::vec::Vec;
 use alloc::boxed::Box;
 
 use super::bitmap_store::{bit, key, BitmapStore, BITMAP_LENGTH};
+use super::IntervalStore;
 
 #[derive(Clone, Eq, PartialEq)]
 pub struct ArrayStore {
@@ -31,6 +32,11 @@ impl ArrayStore {
         ArrayStore { vec: Vec::with_capacity(capacity) }
     }
 
+    /// The number of bytes an array store of the given cardinality takes once serialized.
+    pub fn serialized_byte_size(cardinality: u64) -> usize {
+        cardinality as usize * size_of::<u16>()
+    }
+
     /// The number of total values that can be inserted without needing to reallocate.
     pub fn capacity(&self) -> usize {
         self.vec.capacity()
@@ -149,6 +155,24 @@ impl ArrayStore {
         (pos_end - pos_start) as u64
     }
 
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) {
+        let start = *range.start();
+        let end = *range.end();
+
+        let pos_start = self.vec.binary_search(&start).unwrap_or_else(|x| x);
+        let pos_end = pos_start
+            + match self.vec[pos_start..].binary_search(&end) {
+                Ok(x) => x + 1,
+                Err(x) => x,
+            };
+
+        // Replace the values of the range by the ones that were missing
+        let mut present = self.vec[pos_start..pos_end].iter().peekable();
+        let missing: Vec<u16> =
+            range.filter(|value| present.next_if_eq(&value).is_none()).collect();
+        self.vec.splice(pos_start..pos_end, missing);
+    }
+
     pub fn remove_smallest(&mut self, n: u64) {
         self.vec.rotate_left(n as usize);
         self.vec.truncate(self.vec.len() - n as usize);
@@ -258,7 +282,7 @@ impl ArrayStore {
         self.vec.get(n as usize).cloned()
     }
 
-    pub fn iter(&self) -> core::slice::Iter<u16> {
+    pub fn iter(&self) -> core::slice::Iter<'_, u16> {
         self.vec.iter()
     }
 
@@ -270,6 +294,15 @@ impl ArrayStore {
         &self.vec
     }
 
+    /// Adds the offset to every value, returns the values that stay in the store
+    /// and the ones that overflow, wrapped around, into the next one.
+    pub fn add_offset(&self, offset: u16) -> (ArrayStore, ArrayStore) {
+        let pivot = self.vec.partition_point(|index| index.checked_add(offset).is_some());
+        let low = self.vec[..pivot].iter().map(|&index| index + offset).collect();
+        let high = self.vec[pivot..].iter().map(|&index| index.wrapping_add(offset)).collect();
+        (ArrayStore { vec: low }, ArrayStore { vec: high })
+    }
+
     /// Retains only the elements specified by the predicate.
     pub fn retain(&mut self, mut f: impl FnMut(u16) -> bool) {
         // Idea to avoid branching from "Engineering Fast Indexes for Big Data
@@ -322,6 +355,16 @@ impl Display for Error {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+#[cfg(feature = "std")]
+impl crate::bitmap::serialization::StoreError for Error {
+    fn with_key(self, key: u16) -> crate::bitmap::serialization::DeserializeError {
+        use crate::bitmap::serialization::DeserializeError;
+        match self.kind {
+            ErrorKind::Duplicate | ErrorKind::OutOfOrder => DeserializeError::UnsortedArray { key },
+        }
+    }
+}
+
 impl TryFrom<Vec<u16>> for ArrayStore {
     type Error = Error;
 
@@ -396,6 +439,16 @@ impl BitAndAssign<&BitmapStore> for ArrayStore {
     }
 }
 
+impl BitAndAssign<&IntervalStore> for ArrayStore {
+    fn bitand_assign(&mut self, rhs: &IntervalStore) {
+        let mut intervals = rhs.as_slice().iter().peekable();
+        self.retain(|x| {
+            while intervals.next_if(|iv| iv.end() < x).is_some() {}
+            intervals.peek().map_or(false, |iv| iv.start() <= x)
+        });
+    }
+}
+
 impl Sub<Self> for &ArrayStore {
     type Output = ArrayStore;
 
@@ -435,6 +488,16 @@ impl SubAssign<&BitmapStore> for ArrayStore {
     }
 }
 
+impl SubAssign<&IntervalStore> for ArrayStore {
+    fn sub_assign(&mut self, rhs: &IntervalStore) {
+        let mut intervals = rhs.as_slice().iter().peekable();
+        self.retain(|x| {
+            while intervals.next_if(|iv| iv.end() < x).is_some() {}
+            intervals.peek().map_or(true, |iv| x < iv.start())
+        });
+    }
+}
+
 impl BitXor<Self> for &ArrayStore {
     type Output = ArrayStore;
 
@@ -459,6 +522,7 @@ mod tests {
         match s {
             Store::Array(vec) => vec.vec,
             Store::Bitmap(bits) => bits.to_array_store().vec,
+            Store::Run(runs) => runs.to_array_store().vec,
         }
     }
 
@@ -466,6 +530,7 @@ mod tests {
         match s {
             Store::Array(vec) => Store::Bitmap(vec.to_bitmap_store()),
             Store::Bitmap(..) => s,
+            Store::Run(runs) => Store::Bitmap(runs.to_bitmap_store()),
         }
     }
 
@@ -4,7 +4,7 @@ use core::fmt::{Display, Formatter};
 use core::mem::size_of;
 use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, RangeInclusive, SubAssign};
 
-use super::ArrayStore;
+use super::{ArrayStore, IntervalStore};
 
 #[cfg(not(feature = "std"))]
 use alloc::boxed::Box;
@@ -28,6 +28,11 @@ impl BitmapStore {
         BitmapStore { len: (BITMAP_LENGTH as u64) * 64, bits: Box::new([u64::MAX; BITMAP_LENGTH]) }
     }
 
+    /// The number of bytes a bitmap store takes once serialized.
+    pub fn serialized_byte_size() -> usize {
+        BITMAP_LENGTH * size_of::<u64>()
+    }
+
     pub fn capacity(&self) -> usize {
         BITMAP_LENGTH * u64::BITS as usize
     }
@@ -265,6 +270,54 @@ impl BitmapStore {
         }
     }
 
+    /// Returns the number of values set in the given range.
+    pub fn range_cardinality(&self, range: RangeInclusive<u16>) -> u64 {
+        let start = *range.start();
+        let end = *range.end();
+
+        let (start_key, start_bit) = (key(start), bit(start));
+        let (end_key, end_bit) = (key(end), bit(end));
+
+        if start_key == end_key {
+            let mask = (u64::MAX << start_bit) & (u64::MAX >> (63 - end_bit));
+            return u64::from((self.bits[start_key] & mask).count_ones());
+        }
+
+        let mut len = (self.bits[start_key] & (u64::MAX << start_bit)).count_ones();
+        for word in &self.bits[start_key + 1..end_key] {
+            len += word.count_ones();
+        }
+        len += (self.bits[end_key] & (u64::MAX >> (63 - end_bit))).count_ones();
+        u64::from(len)
+    }
+
+    /// Toggles every value in the given range.
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) {
+        let start = *range.start();
+        let end = *range.end();
+
+        let (start_key, start_bit) = (key(start), bit(start));
+        let (end_key, end_bit) = (key(end), bit(end));
+
+        let mut flip = |index: usize, mask: u64| {
+            let old_w = self.bits[index];
+            let new_w = old_w ^ mask;
+            self.len = self.len + u64::from(new_w.count_ones()) - u64::from(old_w.count_ones());
+            self.bits[index] = new_w;
+        };
+
+        if start_key == end_key {
+            flip(start_key, (u64::MAX << start_bit) & (u64::MAX >> (63 - end_bit)));
+            return;
+        }
+
+        flip(start_key, u64::MAX << start_bit);
+        for index in start_key + 1..end_key {
+            flip(index, u64::MAX);
+        }
+        flip(end_key, u64::MAX >> (63 - end_bit));
+    }
+
     pub fn is_disjoint(&self, other: &BitmapStore) -> bool {
         self.bits.iter().zip(other.bits.iter()).all(|(&i1, &i2)| (i1 & i2) == 0)
     }
@@ -332,6 +385,57 @@ impl BitmapStore {
         None
     }
 
+    /// Adds the offset to every value, returns the values that stay in the store
+    /// and the ones that overflow, wrapped around, into the next one.
+    pub fn add_offset(&self, offset: u16) -> (BitmapStore, BitmapStore) {
+        let (word_shift, bit_shift) = (key(offset), bit(offset));
+        let mut shifted = [Box::new([0; BITMAP_LENGTH]), Box::new([0; BITMAP_LENGTH])];
+        let mut set = |word: usize, value: u64| {
+            shifted[word / BITMAP_LENGTH][word % BITMAP_LENGTH] |= value;
+        };
+        for (key, &value) in self.bits.iter().enumerate() {
+            set(key + word_shift, value << bit_shift);
+            if bit_shift != 0 {
+                set(key + word_shift + 1, value >> (64 - bit_shift));
+            }
+        }
+        let [low, high] = shifted;
+        let len = |bits: &[u64; BITMAP_LENGTH]| bits.iter().map(|v| v.count_ones() as u64).sum();
+        (BitmapStore { len: len(&low), bits: low }, BitmapStore { len: len(&high), bits: high })
+    }
+
+    /// Returns the number of values before each word of the bitmap.
+    pub fn word_prefixes(&self) -> Box<[u16; BITMAP_LENGTH]> {
+        let mut prefixes = Box::new([0; BITMAP_LENGTH]);
+        let mut count: u16 = 0;
+        for (prefix, word) in prefixes.iter_mut().zip(self.bits.iter()) {
+            *prefix = count;
+            // Prefixes are at most 64 * 1023, only the unused count after the last word can overflow
+            count = count.wrapping_add(word.count_ones() as u16);
+        }
+        prefixes
+    }
+
+    /// Same as [`BitmapStore::rank`] using the prefixes returned by [`BitmapStore::word_prefixes`].
+    pub fn rank_with_prefixes(&self, prefixes: &[u16; BITMAP_LENGTH], index: u16) -> u64 {
+        let (key, bit) = (key(index), bit(index));
+        u64::from(prefixes[key]) + (self.bits[key] << (63 - bit)).count_ones() as u64
+    }
+
+    /// Same as [`BitmapStore::select`] using the prefixes returned by
+    /// [`BitmapStore::word_prefixes`].
+    pub fn select_with_prefixes(&self, prefixes: &[u16; BITMAP_LENGTH], n: u16) -> Option<u16> {
+        // The first prefix is always zero, there is at least one prefix <= n
+        let key = prefixes.partition_point(|&prefix| prefix <= n) - 1;
+        let n = u64::from(n - prefixes[key]);
+        let value = self.bits[key];
+        if n < value.count_ones() as u64 {
+            Some((64 * key as u64 + select(value, n)) as u16)
+        } else {
+            None
+        }
+    }
+
     pub fn intersection_len_bitmap(&self, other: &BitmapStore) -> u64 {
         self.bits.iter().zip(other.bits.iter()).map(|(&a, &b)| (a & b).count_ones() as u64).sum()
     }
@@ -360,11 +464,40 @@ impl BitmapStore {
         &self.bits
     }
 
+    pub fn ranges(&self) -> BitmapRanges<'_> {
+        BitmapRanges { bits: &self.bits, front: 0, back: BITMAP_LENGTH as u32 * 64 }
+    }
+
     pub fn clear(&mut self) {
         self.bits.fill(0);
         self.len = 0;
     }
 
+    /// Retains only the elements specified by the predicate.
+    pub fn retain(&mut self, mut f: impl FnMut(u16) -> bool) {
+        for (key, word) in self.bits.iter_mut().enumerate() {
+            let mut bits = *word;
+            while bits != 0 {
+                let bit = bits.trailing_zeros();
+                bits &= bits - 1;
+                if !f((key * 64 + bit as usize) as u16) {
+                    *word &= !(1 << bit);
+                    self.len -= 1;
+                }
+            }
+        }
+    }
+
+    /// Replaces every word by the result of `f` on its index and value.
+    pub fn update_words(&mut self, mut f: impl FnMut(usize, u64) -> u64) {
+        let mut len = 0;
+        for (key, word) in self.bits.iter_mut().enumerate() {
+            *word = f(key, *word);
+            len += u64::from(word.count_ones());
+        }
+        self.len = len;
+    }
+
     /// Set N bits that are currently 1 bit from the lower bit to 0.
     pub fn remove_smallest(&mut self, mut clear_bits: u64) {
         if self.len() < clear_bits {
@@ -451,6 +584,18 @@ impl Display for Error {
 #[cfg(feature = "std")]
 impl std::error::Error for Error {}
 
+#[cfg(feature = "std")]
+impl crate::bitmap::serialization::StoreError for Error {
+    fn with_key(self, key: u16) -> crate::bitmap::serialization::DeserializeError {
+        use crate::bitmap::serialization::DeserializeError;
+        match self.kind {
+            ErrorKind::Cardinality { expected, actual } => {
+                DeserializeError::CardinalityMismatch { key, expected, actual }
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct BitmapIter<B: Borrow<[u64; BITMAP_LENGTH]>> {
     key: u16,
@@ -472,6 +617,37 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> BitmapIter<B> {
         }
     }
 
+    /// Writes the next values of the iterator, combined with the `high` bits, into `dst`
+    /// and returns the number of values written.
+    pub(crate) fn next_many(&mut self, high: u32, dst: &mut [u32]) -> usize {
+        let mut count = 0;
+        while count < dst.len() {
+            if self.value == 0 {
+                if self.key >= self.key_back {
+                    break;
+                }
+                self.key += 1;
+                self.value = if self.key == self.key_back {
+                    self.value_back
+                } else {
+                    // self.key < self.key_back, so it must be in bounds
+                    unsafe { *self.bits.borrow().get_unchecked(self.key as usize) }
+                };
+                continue;
+            }
+            let base = high | (u32::from(self.key) * 64);
+            for slot in &mut dst[count..] {
+                if self.value == 0 {
+                    break;
+                }
+                *slot = base + self.value.trailing_zeros();
+                self.value &= self.value - 1;
+                count += 1;
+            }
+        }
+        count
+    }
+
     /// Advance the iterator to the first value greater than or equal to `n`.
     pub(crate) fn advance_to(&mut self, index: u16) {
         let new_key = key(index) as u16;
@@ -604,6 +780,86 @@ impl<B: Borrow<[u64; BITMAP_LENGTH]>> DoubleEndedIterator for BitmapIter<B> {
 
 impl<B: Borrow<[u64; BITMAP_LENGTH]>> ExactSizeIterator for BitmapIter<B> {}
 
+/// An iterator over the runs of consecutive values of a bitmap store.
+#[derive(Clone)]
+pub struct BitmapRanges<'a> {
+    bits: &'a [u64; BITMAP_LENGTH],
+    // The remaining values are in front..back
+    front: u32,
+    back: u32,
+}
+
+impl BitmapRanges<'_> {
+    /// Returns the position of the first bit equal to `set` in `from..self.back`.
+    fn find_next(&self, from: u32, set: bool) -> Option<u32> {
+        if from >= self.back {
+            return None;
+        }
+        let flip = if set { 0 } else { u64::MAX };
+        let mut key = from as usize / 64;
+        let mut word = (self.bits[key] ^ flip) & (u64::MAX << (from % 64));
+        loop {
+            if word != 0 {
+                let position = key as u32 * 64 + word.trailing_zeros();
+                return (position < self.back).then_some(position);
+            }
+            key += 1;
+            if key * 64 >= self.back as usize {
+                return None;
+            }
+            word = self.bits[key] ^ flip;
+        }
+    }
+
+    /// Returns the position of the last bit equal to `set` in `self.front..to`.
+    fn find_previous(&self, to: u32, set: bool) -> Option<u32> {
+        if to <= self.front {
+            return None;
+        }
+        let flip = if set { 0 } else { u64::MAX };
+        let last = to - 1;
+        let mut key = last as usize / 64;
+        let mut word = (self.bits[key] ^ flip) & (u64::MAX >> (63 - last % 64));
+        loop {
+            if word != 0 {
+                let position = key as u32 * 64 + 63 - word.leading_zeros();
+                return (position >= self.front).then_some(position);
+            }
+            if key * 64 <= self.front as usize {
+                return None;
+            }
+            key -= 1;
+            word = self.bits[key] ^ flip;
+        }
+    }
+}
+
+impl Iterator for BitmapRanges<'_> {
+    type Item = RangeInclusive<u16>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u16>> {
+        let Some(start) = self.find_next(self.front, true) else {
+            self.front = self.back;
+            return None;
+        };
+        let end = self.find_next(start, false).unwrap_or(self.back);
+        self.front = end;
+        Some(start as u16..=(end - 1) as u16)
+    }
+}
+
+impl DoubleEndedIterator for BitmapRanges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u16>> {
+        let Some(end) = self.find_previous(self.back, true) else {
+            self.back = self.front;
+            return None;
+        };
+        let start = self.find_previous(end, false).map_or(self.front, |unset| unset + 1);
+        self.back = start;
+        Some(start as u16..=end as u16)
+    }
+}
+
 #[inline]
 pub fn key(index: u16) -> usize {
     index as usize / 64
@@ -667,6 +923,31 @@ impl SubAssign<&ArrayStore> for BitmapStore {
     }
 }
 
+impl BitOrAssign<&IntervalStore> for BitmapStore {
+    fn bitor_assign(&mut self, rhs: &IntervalStore) {
+        for interval in rhs.as_slice() {
+            self.insert_range(interval.range());
+        }
+    }
+}
+
+impl BitAndAssign<&IntervalStore> for BitmapStore {
+    fn bitand_assign(&mut self, rhs: &IntervalStore) {
+        for gap in rhs.gaps() {
+            self.remove_range(gap);
+        }
+    }
+}
+
+impl SubAssign<&IntervalStore> for BitmapStore {
+    #[allow(clippy::suspicious_op_assign_impl)]
+    fn sub_assign(&mut self, rhs: &IntervalStore) {
+        for interval in rhs.as_slice() {
+            self.remove_range(interval.range());
+        }
+    }
+}
+
 impl BitXorAssign<&Self> for BitmapStore {
     fn bitxor_assign(&mut self, rhs: &Self) {
         op_bitmaps(self, rhs, BitXorAssign::bitxor_assign);
@@ -687,6 +968,14 @@ impl BitXorAssign<&ArrayStore> for BitmapStore {
     }
 }
 
+impl BitXorAssign<&IntervalStore> for BitmapStore {
+    fn bitxor_assign(&mut self, rhs: &IntervalStore) {
+        for interval in rhs.as_slice() {
+            self.flip_range(interval.range());
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,1228 @@
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt::{Display, Formatter};
+use core::ops::{BitAnd, BitOr, BitXor, RangeInclusive, Sub};
+
+use super::{ArrayStore, BitmapStore};
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// The number of bytes used to store the number of runs of a run container.
+pub const RUN_NUM_BYTES: usize = 2;
+/// The number of bytes used to store a single run (start and length - 1).
+pub const RUN_ELEMENT_BYTES: usize = 4;
+
+/// An inclusive range of consecutive values inside a run container.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub struct Interval {
+    start: u16,
+    end: u16,
+}
+
+impl Interval {
+    /// Creates an interval containing every value between `start` and `end` (inclusive).
+    ///
+    /// # Panics
+    ///
+    /// When debug_assertions are enabled and `start > end`
+    #[inline]
+    pub fn new(start: u16, end: u16) -> Interval {
+        debug_assert!(start <= end, "interval start > end");
+        Interval { start, end }
+    }
+
+    #[inline]
+    pub fn start(&self) -> u16 {
+        self.start
+    }
+
+    #[inline]
+    pub fn end(&self) -> u16 {
+        self.end
+    }
+
+    #[inline]
+    pub fn len(&self) -> u64 {
+        u64::from(self.end - self.start) + 1
+    }
+
+    #[inline]
+    pub fn range(&self) -> RangeInclusive<u16> {
+        self.start..=self.end
+    }
+
+    /// Returns the number of values shared by this interval and `range`.
+    #[inline]
+    fn overlap_len(&self, start: u16, end: u16) -> u64 {
+        let start = self.start.max(start);
+        let end = self.end.min(end);
+        if start <= end {
+            u64::from(end - start) + 1
+        } else {
+            0
+        }
+    }
+}
+
+/// A store of sorted, non-overlapping and non-adjacent intervals,
+/// also known as a run container.
+#[derive(Clone, Eq, PartialEq)]
+pub struct IntervalStore {
+    len: u64,
+    vec: Vec<Interval>,
+}
+
+impl IntervalStore {
+    pub fn new() -> IntervalStore {
+        IntervalStore { len: 0, vec: vec![] }
+    }
+
+    pub fn full() -> IntervalStore {
+        IntervalStore { len: 1 << 16, vec: vec![Interval::new(0, u16::MAX)] }
+    }
+
+    /// Creates a store from intervals that are known to be valid, counting their values.
+    fn from_valid_vec(vec: Vec<Interval>) -> IntervalStore {
+        IntervalStore { len: vec.iter().map(Interval::len).sum(), vec }
+    }
+
+    ///
+    /// Create a new IntervalStore from a given vec of intervals
+    /// It is up to the caller to ensure the intervals are sorted, non-overlapping and non-adjacent
+    /// Favor `try_from` / `try_into` for cases in which these invariants should be checked
+    ///
+    /// # Panics
+    ///
+    /// When debug_assertions are enabled and the above invariants are not met
+    #[inline]
+    pub fn from_vec_unchecked(vec: Vec<Interval>) -> IntervalStore {
+        if cfg!(debug_assertions) {
+            vec.try_into().unwrap()
+        } else {
+            IntervalStore::from_valid_vec(vec)
+        }
+    }
+
+    /// Creates a new IntervalStore from a vec of intervals holding `len` values,
+    /// checking that the intervals are sorted, non-overlapping and non-adjacent
+    /// and that they hold exactly `len` values.
+    pub fn try_from_runs(len: u64, vec: Vec<Interval>) -> Result<IntervalStore, Error> {
+        let store = IntervalStore::try_from(vec)?;
+        let actual = store.len();
+        if actual != len {
+            return Err(Error {
+                index: store.vec.len(),
+                kind: ErrorKind::Cardinality { expected: len, actual },
+            });
+        }
+        Ok(store)
+    }
+
+    pub fn from_array_store(array: &ArrayStore) -> IntervalStore {
+        let mut vec: Vec<Interval> = Vec::new();
+        for &value in array.iter() {
+            match vec.last_mut() {
+                Some(last) if u32::from(last.end) + 1 == u32::from(value) => last.end = value,
+                _ => vec.push(Interval::new(value, value)),
+            }
+        }
+        IntervalStore { len: array.len(), vec }
+    }
+
+    pub fn from_bitmap_store(bitmap: &BitmapStore) -> IntervalStore {
+        let bits = bitmap.as_array();
+        let mut vec = Vec::new();
+
+        let mut key = 0;
+        let mut word = bits[0];
+        loop {
+            while word == 0 && key + 1 < bits.len() {
+                key += 1;
+                word = bits[key];
+            }
+            if word == 0 {
+                break;
+            }
+            let start = 64 * key + word.trailing_zeros() as usize;
+
+            // Fill the zeros below the start of the run with ones
+            // to find where the run stops.
+            let mut ones = word | (word - 1);
+            while ones == u64::MAX && key + 1 < bits.len() {
+                key += 1;
+                ones = bits[key];
+            }
+            if ones == u64::MAX {
+                vec.push(Interval::new(start as u16, u16::MAX));
+                break;
+            }
+            let end = 64 * key + (!ones).trailing_zeros() as usize - 1;
+            vec.push(Interval::new(start as u16, end as u16));
+
+            // Clear the trailing ones that we just consumed
+            word = ones & (ones + 1);
+        }
+
+        IntervalStore { len: bitmap.len(), vec }
+    }
+
+    /// Returns the number of runs needed to represent the given array store.
+    pub fn count_runs_array(array: &ArrayStore) -> usize {
+        let values = array.as_slice();
+        match values.first() {
+            None => 0,
+            Some(_) => 1 + values.windows(2).filter(|w| w[0] + 1 != w[1]).count(),
+        }
+    }
+
+    /// Returns the number of runs needed to represent the given bitmap store.
+    pub fn count_runs_bitmap(bitmap: &BitmapStore) -> usize {
+        let bits = bitmap.as_array();
+        let mut runs = 0;
+        for window in bits.windows(2) {
+            let (word, next) = (window[0], window[1]);
+            // Count the bits that end a run: set bits followed by an unset bit
+            runs += ((word << 1) & !word).count_ones() as usize + ((word >> 63) & !next) as usize;
+        }
+        let last = bits[bits.len() - 1];
+        runs + ((last << 1) & !last).count_ones() as usize + (last >> 63) as usize
+    }
+
+    /// The number of bytes a run store with the given amount of runs takes once serialized.
+    pub fn serialized_byte_size(runs: usize) -> usize {
+        RUN_NUM_BYTES + runs * RUN_ELEMENT_BYTES
+    }
+
+    /// The number of bytes this store takes once serialized.
+    pub fn byte_size(&self) -> usize {
+        IntervalStore::serialized_byte_size(self.vec.len())
+    }
+
+    /// The number of total runs that can be inserted without needing to reallocate.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    /// Returns the number of runs in this store.
+    pub fn run_amount(&self) -> usize {
+        self.vec.len()
+    }
+
+    /// Returns the index of the first interval that ends at or after `index`.
+    #[inline]
+    fn find(&self, index: u16) -> usize {
+        self.vec.partition_point(|iv| iv.end < index)
+    }
+
+    pub fn insert(&mut self, index: u16) -> bool {
+        let pos = self.find(index);
+        if self.vec.get(pos).map_or(false, |iv| iv.start <= index) {
+            return false;
+        }
+
+        let merge_prev = pos > 0 && self.vec[pos - 1].end + 1 == index;
+        let merge_next =
+            self.vec.get(pos).map_or(false, |iv| index.checked_add(1) == Some(iv.start));
+        match (merge_prev, merge_next) {
+            (true, true) => {
+                self.vec[pos - 1].end = self.vec[pos].end;
+                self.vec.remove(pos);
+            }
+            (true, false) => self.vec[pos - 1].end = index,
+            (false, true) => self.vec[pos].start = index,
+            (false, false) => self.vec.insert(pos, Interval::new(index, index)),
+        }
+        self.len += 1;
+        true
+    }
+
+    pub fn insert_range(&mut self, range: RangeInclusive<u16>) -> u64 {
+        let start = *range.start();
+        let end = *range.end();
+
+        // Find the intervals that overlap or are adjacent to the range, they will be merged.
+        let first = self.vec.partition_point(|iv| u32::from(iv.end) + 1 < u32::from(start));
+        let last = first
+            + self.vec[first..].partition_point(|iv| u32::from(iv.start) <= u32::from(end) + 1);
+
+        let existed: u64 = self.vec[first..last].iter().map(|iv| iv.overlap_len(start, end)).sum();
+        let merged = match (self.vec[first..last].first(), self.vec[first..last].last()) {
+            (Some(lo), Some(hi)) => Interval::new(lo.start.min(start), hi.end.max(end)),
+            _ => Interval::new(start, end),
+        };
+        self.vec.splice(first..last, [merged]);
+
+        let added = u64::from(end - start) + 1 - existed;
+        self.len += added;
+        added
+    }
+
+    pub fn push(&mut self, index: u16) -> bool {
+        if self.max().map_or(true, |max| max < index) {
+            self.push_unchecked(index);
+            true
+        } else {
+            false
+        }
+    }
+
+    ///
+    /// Pushes `index` at the end of the store.
+    /// It is up to the caller to have validated index > self.max()
+    ///
+    /// # Panics
+    ///
+    /// If debug_assertions enabled and index is > self.max()
+    pub(crate) fn push_unchecked(&mut self, index: u16) {
+        match self.vec.last_mut() {
+            Some(last) => {
+                debug_assert!(index > last.end, "store max >= index");
+                if last.end + 1 == index {
+                    last.end = index;
+                } else {
+                    self.vec.push(Interval::new(index, index));
+                }
+            }
+            None => self.vec.push(Interval::new(index, index)),
+        }
+        self.len += 1;
+    }
+
+    /// Pushes an interval at the end of the store, merging it with the last one when they touch.
+    /// It is up to the caller to have validated interval.start() > self.max()
+    pub(crate) fn push_interval_unchecked(&mut self, interval: Interval) {
+        match self.vec.last_mut() {
+            Some(last) if u32::from(last.end) + 1 >= u32::from(interval.start) => {
+                if interval.end > last.end {
+                    self.len += u64::from(interval.end - last.end);
+                    last.end = interval.end;
+                }
+            }
+            _ => {
+                self.len += interval.len();
+                self.vec.push(interval);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: u16) -> bool {
+        let pos = self.find(index);
+        let interval = match self.vec.get_mut(pos) {
+            Some(iv) if iv.start <= index => iv,
+            _ => return false,
+        };
+
+        if interval.start == interval.end {
+            self.vec.remove(pos);
+        } else if interval.start == index {
+            interval.start += 1;
+        } else if interval.end == index {
+            interval.end -= 1;
+        } else {
+            let right = Interval::new(index + 1, interval.end);
+            interval.end = index - 1;
+            self.vec.insert(pos + 1, right);
+        }
+        self.len -= 1;
+        true
+    }
+
+    pub fn remove_range(&mut self, range: RangeInclusive<u16>) -> u64 {
+        let start = *range.start();
+        let end = *range.end();
+
+        let first = self.find(start);
+        let last = first + self.vec[first..].partition_point(|iv| iv.start <= end);
+        if first == last {
+            return 0;
+        }
+
+        let removed = self.vec[first..last].iter().map(|iv| iv.overlap_len(start, end)).sum();
+
+        let (lo, hi) = (self.vec[first], self.vec[last - 1]);
+        let left = (lo.start < start).then(|| Interval::new(lo.start, start - 1));
+        let right = (hi.end > end).then(|| Interval::new(end + 1, hi.end));
+        self.vec.splice(first..last, left.into_iter().chain(right));
+
+        self.len -= removed;
+        removed
+    }
+
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) {
+        let start = *range.start();
+        let end = *range.end();
+
+        let mut first = self.find(start);
+        let mut last = first + self.vec[first..].partition_point(|iv| iv.start <= end);
+
+        let existed: u64 = self.vec[first..last].iter().map(|iv| iv.overlap_len(start, end)).sum();
+        self.len = self.len + u64::from(end - start) + 1 - 2 * existed;
+
+        // The parts of the overlapping intervals outside of the range are kept
+        // and the gaps between them inside of the range are filled.
+        let mut flipped = Vec::with_capacity(last - first + 1);
+        let mut next = Some(start);
+        for iv in &self.vec[first..last] {
+            if iv.start < start {
+                flipped.push(Interval::new(iv.start, start - 1));
+            }
+            if let Some(gap_start) = next.filter(|&n| n < iv.start) {
+                flipped.push(Interval::new(gap_start, iv.start - 1));
+            }
+            if iv.end > end {
+                flipped.push(Interval::new(end + 1, iv.end));
+            }
+            next = iv.end.checked_add(1);
+        }
+        if let Some(gap_start) = next.filter(|&n| n <= end) {
+            flipped.push(Interval::new(gap_start, end));
+        }
+
+        // Merge with the untouched neighbours when they become adjacent
+        if let (Some(prev), Some(head)) = (first.checked_sub(1), flipped.first_mut()) {
+            if self.vec[prev].end.checked_add(1) == Some(head.start) {
+                head.start = self.vec[prev].start;
+                first -= 1;
+            }
+        }
+        if let (Some(next), Some(tail)) = (self.vec.get(last), flipped.last_mut()) {
+            if tail.end.checked_add(1) == Some(next.start) {
+                tail.end = next.end;
+                last += 1;
+            }
+        }
+
+        self.vec.splice(first..last, flipped);
+    }
+
+    /// Retains only the elements specified by the predicate.
+    pub fn retain(&mut self, mut f: impl FnMut(u16) -> bool) {
+        let mut retained: Vec<Interval> = Vec::new();
+        for value in self.iter() {
+            if f(value) {
+                match retained.last_mut() {
+                    Some(last) if last.end + 1 == value => last.end = value,
+                    _ => retained.push(Interval::new(value, value)),
+                }
+            }
+        }
+        *self = IntervalStore::from_valid_vec(retained);
+    }
+
+    pub fn remove_smallest(&mut self, mut n: u64) {
+        self.len = self.len.saturating_sub(n);
+        let position = self.vec.iter().position(|iv| {
+            let len = iv.len();
+            if len <= n {
+                n -= len;
+                false
+            } else {
+                true
+            }
+        });
+        match position {
+            Some(position) => {
+                self.vec.drain(..position);
+                // n is smaller than the length of the interval, so it fits in an u16
+                self.vec[0].start += n as u16;
+            }
+            None => self.vec.clear(),
+        }
+    }
+
+    pub fn remove_biggest(&mut self, mut n: u64) {
+        self.len = self.len.saturating_sub(n);
+        let position = self.vec.iter().rposition(|iv| {
+            let len = iv.len();
+            if len <= n {
+                n -= len;
+                false
+            } else {
+                true
+            }
+        });
+        match position {
+            Some(position) => {
+                self.vec.truncate(position + 1);
+                // n is smaller than the length of the interval, so it fits in an u16
+                self.vec[position].end -= n as u16;
+            }
+            None => self.vec.clear(),
+        }
+    }
+
+    pub fn contains(&self, index: u16) -> bool {
+        self.vec.get(self.find(index)).map_or(false, |iv| iv.start <= index)
+    }
+
+    pub fn contains_range(&self, range: RangeInclusive<u16>) -> bool {
+        let start = *range.start();
+        let end = *range.end();
+        // Intervals are never adjacent, so the whole range must be inside a single one
+        self.vec.get(self.find(start)).map_or(false, |iv| iv.start <= start && end <= iv.end)
+    }
+
+    pub fn is_disjoint(&self, other: &Self) -> bool {
+        let (mut i1, mut i2) = (self.vec.iter(), other.vec.iter());
+        let (mut iv1, mut iv2) = (i1.next(), i2.next());
+        while let (Some(a), Some(b)) = (iv1, iv2) {
+            if a.end < b.start {
+                iv1 = i1.next();
+            } else if b.end < a.start {
+                iv2 = i2.next();
+            } else {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn is_disjoint_array(&self, array: &ArrayStore) -> bool {
+        let mut intervals = self.vec.iter().peekable();
+        array.iter().all(|&value| {
+            while intervals.next_if(|iv| iv.end < value).is_some() {}
+            intervals.peek().map_or(true, |iv| value < iv.start)
+        })
+    }
+
+    pub fn is_disjoint_bitmap(&self, bitmap: &BitmapStore) -> bool {
+        self.vec.iter().all(|iv| bitmap.range_cardinality(iv.range()) == 0)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        let mut others = other.vec.iter().peekable();
+        self.vec.iter().all(|iv| {
+            while others.next_if(|o| o.end < iv.start).is_some() {}
+            others.peek().map_or(false, |o| o.start <= iv.start && iv.end <= o.end)
+        })
+    }
+
+    pub fn is_subset_array(&self, array: &ArrayStore) -> bool {
+        self.len() <= array.len() && self.vec.iter().all(|iv| array.contains_range(iv.range()))
+    }
+
+    pub fn is_subset_bitmap(&self, bitmap: &BitmapStore) -> bool {
+        self.len() <= bitmap.len() && self.vec.iter().all(|iv| bitmap.contains_range(iv.range()))
+    }
+
+    /// Returns `true` if all the values of the array are contained in this store.
+    pub fn is_superset_array(&self, array: &ArrayStore) -> bool {
+        let mut intervals = self.vec.iter().peekable();
+        array.iter().all(|&value| {
+            while intervals.next_if(|iv| iv.end < value).is_some() {}
+            intervals.peek().map_or(false, |iv| iv.start <= value)
+        })
+    }
+
+    /// Returns `true` if all the values of the bitmap are contained in this store.
+    pub fn is_superset_bitmap(&self, bitmap: &BitmapStore) -> bool {
+        bitmap.len() <= self.len() && self.gaps().all(|gap| bitmap.range_cardinality(gap) == 0)
+    }
+
+    pub fn intersection_len(&self, other: &Self) -> u64 {
+        let (mut i1, mut i2) = (self.vec.iter(), other.vec.iter());
+        let (mut iv1, mut iv2) = (i1.next(), i2.next());
+        let mut len = 0;
+        while let (Some(a), Some(b)) = (iv1, iv2) {
+            len += a.overlap_len(b.start, b.end);
+            if a.end <= b.end {
+                iv1 = i1.next();
+            } else {
+                iv2 = i2.next();
+            }
+        }
+        len
+    }
+
+    pub fn intersection_len_array(&self, array: &ArrayStore) -> u64 {
+        let mut intervals = self.vec.iter().peekable();
+        array
+            .iter()
+            .filter(|&&value| {
+                while intervals.next_if(|iv| iv.end < value).is_some() {}
+                intervals.peek().map_or(false, |iv| iv.start <= value)
+            })
+            .count() as u64
+    }
+
+    pub fn intersection_len_bitmap(&self, bitmap: &BitmapStore) -> u64 {
+        self.vec.iter().map(|iv| bitmap.range_cardinality(iv.range())).sum()
+    }
+
+    /// Adds the offset to every value, returns the values that stay in the store
+    /// and the ones that overflow, wrapped around, into the next one.
+    pub fn add_offset(&self, offset: u16) -> (IntervalStore, IntervalStore) {
+        let mut low = Vec::new();
+        let mut high = Vec::new();
+        for iv in &self.vec {
+            match (iv.start.checked_add(offset), iv.end.checked_add(offset)) {
+                (Some(start), Some(end)) => low.push(Interval::new(start, end)),
+                (Some(start), None) => {
+                    low.push(Interval::new(start, u16::MAX));
+                    high.push(Interval::new(0, iv.end.wrapping_add(offset)));
+                }
+                _ => high.push(Interval::new(
+                    iv.start.wrapping_add(offset),
+                    iv.end.wrapping_add(offset),
+                )),
+            }
+        }
+        (IntervalStore::from_valid_vec(low), IntervalStore::from_valid_vec(high))
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn min(&self) -> Option<u16> {
+        self.vec.first().map(|iv| iv.start)
+    }
+
+    pub fn max(&self) -> Option<u16> {
+        self.vec.last().map(|iv| iv.end)
+    }
+
+    pub fn rank(&self, index: u16) -> u64 {
+        let mut rank = 0;
+        for iv in &self.vec {
+            if iv.end <= index {
+                rank += iv.len();
+            } else {
+                if iv.start <= index {
+                    rank += u64::from(index - iv.start) + 1;
+                }
+                break;
+            }
+        }
+        rank
+    }
+
+    pub fn select(&self, n: u16) -> Option<u16> {
+        let mut n = u64::from(n);
+        for iv in &self.vec {
+            let len = iv.len();
+            if n < len {
+                return Some(iv.start + n as u16);
+            }
+            n -= len;
+        }
+        None
+    }
+
+    pub fn to_array_store(&self) -> ArrayStore {
+        let mut vec = Vec::with_capacity(self.len() as usize);
+        for iv in &self.vec {
+            vec.extend(iv.range());
+        }
+        ArrayStore::from_vec_unchecked(vec)
+    }
+
+    pub fn to_bitmap_store(&self) -> BitmapStore {
+        let mut bits = BitmapStore::new();
+        for iv in &self.vec {
+            bits.insert_range(iv.range());
+        }
+        bits
+    }
+
+    pub fn iter(&self) -> RunIter<&[Interval]> {
+        RunIter::new(&self.vec)
+    }
+
+    pub fn into_iter(self) -> RunIter<Vec<Interval>> {
+        RunIter::new(self.vec)
+    }
+
+    pub fn as_slice(&self) -> &[Interval] {
+        &self.vec
+    }
+
+    /// Iterates over the ranges of values that are not contained in this store.
+    pub fn gaps(&self) -> impl Iterator<Item = RangeInclusive<u16>> + '_ {
+        let mut next_start = Some(0u16);
+        self.vec.iter().map(Some).chain(core::iter::once(None)).filter_map(move |iv| {
+            let start = next_start?;
+            match iv {
+                Some(iv) => {
+                    next_start = iv.end.checked_add(1);
+                    (start < iv.start).then(|| start..=iv.start - 1)
+                }
+                None => {
+                    next_start = None;
+                    Some(start..=u16::MAX)
+                }
+            }
+        })
+    }
+}
+
+impl Default for IntervalStore {
+    fn default() -> Self {
+        IntervalStore::new()
+    }
+}
+
+#[derive(Debug)]
+pub struct Error {
+    index: usize,
+    kind: ErrorKind,
+}
+
+#[derive(Debug)]
+pub enum ErrorKind {
+    StartGreaterThanEnd,
+    Overlapping,
+    Adjacent,
+    Cardinality { expected: u64, actual: u64 },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self.kind {
+            ErrorKind::StartGreaterThanEnd => {
+                write!(f, "An interval starts after its end at index: {}", self.index)
+            }
+            ErrorKind::Overlapping => {
+                write!(f, "An interval overlaps or is out of order at index: {}", self.index)
+            }
+            ErrorKind::Adjacent => {
+                write!(f, "An interval is adjacent to the previous one at index: {}", self.index)
+            }
+            ErrorKind::Cardinality { expected, actual } => {
+                write!(f, "Expected cardinality was {expected} but was {actual}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+#[cfg(feature = "std")]
+impl crate::bitmap::serialization::StoreError for Error {
+    fn with_key(self, key: u16) -> crate::bitmap::serialization::DeserializeError {
+        use crate::bitmap::serialization::DeserializeError;
+        match self.kind {
+            ErrorKind::Cardinality { expected, actual } => {
+                DeserializeError::CardinalityMismatch { key, expected, actual }
+            }
+            _ => DeserializeError::InvalidRun { key },
+        }
+    }
+}
+
+impl TryFrom<Vec<Interval>> for IntervalStore {
+    type Error = Error;
+
+    fn try_from(value: Vec<Interval>) -> Result<Self, Self::Error> {
+        let mut prev_end: Option<u16> = None;
+        for (i, iv) in value.iter().enumerate() {
+            if iv.start > iv.end {
+                return Err(Error { index: i, kind: ErrorKind::StartGreaterThanEnd });
+            }
+            if let Some(prev_end) = prev_end {
+                match iv.start.cmp(&prev_end) {
+                    Ordering::Less | Ordering::Equal => {
+                        return Err(Error { index: i, kind: ErrorKind::Overlapping })
+                    }
+                    Ordering::Greater if iv.start == prev_end + 1 => {
+                        return Err(Error { index: i, kind: ErrorKind::Adjacent })
+                    }
+                    Ordering::Greater => (),
+                }
+            }
+            prev_end = Some(iv.end);
+        }
+
+        Ok(IntervalStore::from_valid_vec(value))
+    }
+}
+
+impl BitOr<Self> for &IntervalStore {
+    type Output = IntervalStore;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        let mut store =
+            IntervalStore { len: 0, vec: Vec::with_capacity(self.vec.len() + rhs.vec.len()) };
+        let (mut i1, mut i2) = (self.vec.iter().peekable(), rhs.vec.iter().peekable());
+        loop {
+            let next = match (i1.peek(), i2.peek()) {
+                (Some(a), Some(b)) if a.start <= b.start => i1.next(),
+                (Some(_), Some(_)) => i2.next(),
+                (Some(_), None) => i1.next(),
+                (None, Some(_)) => i2.next(),
+                (None, None) => break,
+            };
+            if let Some(&interval) = next {
+                store.push_interval_unchecked(interval);
+            }
+        }
+        store
+    }
+}
+
+impl BitAnd<Self> for &IntervalStore {
+    type Output = IntervalStore;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        let mut vec = Vec::new();
+        let (mut i1, mut i2) = (self.vec.iter(), rhs.vec.iter());
+        let (mut iv1, mut iv2) = (i1.next(), i2.next());
+        while let (Some(a), Some(b)) = (iv1, iv2) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start <= end {
+                vec.push(Interval::new(start, end));
+            }
+            if a.end <= b.end {
+                iv1 = i1.next();
+            } else {
+                iv2 = i2.next();
+            }
+        }
+        IntervalStore::from_valid_vec(vec)
+    }
+}
+
+impl Sub<Self> for &IntervalStore {
+    type Output = IntervalStore;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        let mut vec = Vec::with_capacity(self.vec.len());
+        let mut others = rhs.vec.iter().peekable();
+        for iv in &self.vec {
+            // u32 avoids overflowing when a removed interval ends at u16::MAX
+            let mut start = u32::from(iv.start);
+            let end = u32::from(iv.end);
+            while others.next_if(|o| u32::from(o.end) < start).is_some() {}
+            while let Some(o) = others.peek() {
+                if u32::from(o.start) > end {
+                    break;
+                }
+                if u32::from(o.start) > start {
+                    vec.push(Interval::new(start as u16, o.start - 1));
+                }
+                start = u32::from(o.end) + 1;
+                if start > end {
+                    break;
+                }
+                others.next();
+            }
+            if start <= end {
+                vec.push(Interval::new(start as u16, end as u16));
+            }
+        }
+        IntervalStore::from_valid_vec(vec)
+    }
+}
+
+impl BitXor<Self> for &IntervalStore {
+    type Output = IntervalStore;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        // Each interval is seen as two boundaries `[start, end + 1)` where the membership toggles.
+        // The boundaries of the symmetric difference are the boundaries of both sides
+        // where the ones that are shared cancel each other.
+        fn boundaries(vec: &[Interval]) -> impl Iterator<Item = u32> + '_ {
+            vec.iter().flat_map(|iv| [u32::from(iv.start), u32::from(iv.end) + 1])
+        }
+
+        let mut vec = Vec::with_capacity(self.vec.len() + rhs.vec.len());
+        let (mut b1, mut b2) = (boundaries(&self.vec).peekable(), boundaries(&rhs.vec).peekable());
+        let mut open: Option<u32> = None;
+        loop {
+            let boundary = match (b1.peek(), b2.peek()) {
+                (Some(x), Some(y)) => match x.cmp(y) {
+                    Ordering::Less => b1.next(),
+                    Ordering::Greater => b2.next(),
+                    Ordering::Equal => {
+                        b1.next();
+                        b2.next();
+                        continue;
+                    }
+                },
+                (Some(_), None) => b1.next(),
+                (None, Some(_)) => b2.next(),
+                (None, None) => break,
+            };
+            let boundary = boundary.expect("a boundary was peeked");
+            match open.take() {
+                Some(start) => vec.push(Interval::new(start as u16, (boundary - 1) as u16)),
+                None => open = Some(boundary),
+            }
+        }
+        IntervalStore::from_valid_vec(vec)
+    }
+}
+
+/// An iterator over the values of a run container.
+#[derive(Clone)]
+pub struct RunIter<I: Borrow<[Interval]>> {
+    intervals: I,
+    /// The index of the interval and the next value to return from the front.
+    front: (usize, u16),
+    /// The index of the interval and the next value to return from the back.
+    back: (usize, u16),
+    /// The number of values left to return.
+    len: u64,
+}
+
+impl<I: Borrow<[Interval]>> RunIter<I> {
+    fn new(intervals: I) -> RunIter<I> {
+        let slice = intervals.borrow();
+        let len = slice.iter().map(Interval::len).sum();
+        let front = slice.first().map_or((0, 0), |iv| (0, iv.start));
+        let back = slice.last().map_or((0, 0), |iv| (slice.len() - 1, iv.end));
+        RunIter { intervals, front, back, len }
+    }
+
+    /// Writes the next values of the iterator, combined with the `high` bits, into `dst`
+    /// and returns the number of values written.
+    pub(crate) fn next_many(&mut self, high: u32, dst: &mut [u32]) -> usize {
+        let intervals = self.intervals.borrow();
+        let mut count = 0;
+        while count < dst.len() && self.len != 0 {
+            let (idx, value) = self.front;
+            let end = if idx == self.back.0 { self.back.1 } else { intervals[idx].end };
+            let n = (u64::from(end - value) + 1).min((dst.len() - count) as u64) as usize;
+            for (slot, index) in dst[count..count + n].iter_mut().zip(u32::from(value)..) {
+                *slot = high | index;
+            }
+            count += n;
+            self.len -= n as u64;
+            if self.len != 0 {
+                let last = value + (n - 1) as u16;
+                self.front = if last == intervals[idx].end {
+                    (idx + 1, intervals[idx + 1].start)
+                } else {
+                    (idx, last + 1)
+                };
+            }
+        }
+        count
+    }
+
+    /// Advance the iterator to the first value greater than or equal to `n`.
+    pub(crate) fn advance_to(&mut self, n: u16) {
+        let (front_idx, front_value) = self.front;
+        if self.len == 0 || n <= front_value {
+            return;
+        }
+
+        let intervals = self.intervals.borrow();
+        let (back_idx, back_value) = self.back;
+        let idx = front_idx + intervals[front_idx..=back_idx].partition_point(|iv| iv.end < n);
+        if idx > back_idx {
+            self.len = 0;
+            return;
+        }
+        let value = n.max(intervals[idx].start);
+        if idx == back_idx && value > back_value {
+            self.len = 0;
+            return;
+        }
+
+        let skipped = if idx == front_idx {
+            u64::from(value - front_value)
+        } else {
+            u64::from(intervals[front_idx].end - front_value)
+                + 1
+                + intervals[front_idx + 1..idx].iter().map(Interval::len).sum::<u64>()
+                + u64::from(value - intervals[idx].start)
+        };
+        self.len -= skipped;
+        self.front = (idx, value);
+    }
+
+    /// Advance the back of the iterator to the first value less than or equal to `n`.
+    pub(crate) fn advance_back_to(&mut self, n: u16) {
+        let (back_idx, back_value) = self.back;
+        if self.len == 0 || n >= back_value {
+            return;
+        }
+
+        let intervals = self.intervals.borrow();
+        let (front_idx, front_value) = self.front;
+        let after = front_idx + intervals[front_idx..=back_idx].partition_point(|iv| iv.start <= n);
+        let idx = match after.checked_sub(1) {
+            Some(idx) if idx >= front_idx => idx,
+            _ => {
+                self.len = 0;
+                return;
+            }
+        };
+        let value = n.min(intervals[idx].end);
+        if idx == front_idx && value < front_value {
+            self.len = 0;
+            return;
+        }
+
+        let skipped = if idx == back_idx {
+            u64::from(back_value - value)
+        } else {
+            u64::from(back_value - intervals[back_idx].start)
+                + 1
+                + intervals[idx + 1..back_idx].iter().map(Interval::len).sum::<u64>()
+                + u64::from(intervals[idx].end - value)
+        };
+        self.len -= skipped;
+        self.back = (idx, value);
+    }
+}
+
+impl<I: Borrow<[Interval]>> Iterator for RunIter<I> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+
+        let (idx, value) = self.front;
+        if self.len != 0 {
+            let intervals = self.intervals.borrow();
+            self.front = if value == intervals[idx].end {
+                (idx + 1, intervals[idx + 1].start)
+            } else {
+                (idx, value + 1)
+            };
+        }
+        Some(value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len as usize, Some(self.len as usize))
+    }
+
+    fn count(self) -> usize
+    where
+        Self: Sized,
+    {
+        self.len as usize
+    }
+
+    fn nth(&mut self, n: usize) -> Option<u16> {
+        let mut n = n as u64;
+        if n >= self.len {
+            self.len = 0;
+            return None;
+        }
+        self.len -= n;
+
+        let intervals = self.intervals.borrow();
+        let (mut idx, mut value) = self.front;
+        loop {
+            let remaining = u64::from(intervals[idx].end - value) + 1;
+            if n < remaining {
+                value += n as u16;
+                break;
+            }
+            n -= remaining;
+            idx += 1;
+            value = intervals[idx].start;
+        }
+        self.front = (idx, value);
+        self.next()
+    }
+}
+
+impl<I: Borrow<[Interval]>> DoubleEndedIterator for RunIter<I> {
+    fn next_back(&mut self) -> Option<u16> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+
+        let (idx, value) = self.back;
+        if self.len != 0 {
+            let intervals = self.intervals.borrow();
+            self.back = if value == intervals[idx].start {
+                (idx - 1, intervals[idx - 1].end)
+            } else {
+                (idx, value - 1)
+            };
+        }
+        Some(value)
+    }
+}
+
+impl<I: Borrow<[Interval]>> ExactSizeIterator for RunIter<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(intervals: &[(u16, u16)]) -> IntervalStore {
+        IntervalStore::from_vec_unchecked(
+            intervals.iter().map(|&(start, end)| Interval::new(start, end)).collect(),
+        )
+    }
+
+    fn values(store: &IntervalStore) -> Vec<u16> {
+        store.iter().collect()
+    }
+
+    #[test]
+    fn test_interval_insert() {
+        let mut s = store(&[(1, 3), (7, 9)]);
+        assert!(!s.insert(2));
+        assert!(s.insert(5));
+        assert_eq!(s, store(&[(1, 3), (5, 5), (7, 9)]));
+        assert!(s.insert(4));
+        assert!(s.insert(6));
+        assert_eq!(s, store(&[(1, 9)]));
+        assert!(s.insert(u16::MAX));
+        assert!(s.insert(0));
+        assert_eq!(s, store(&[(0, 9), (u16::MAX, u16::MAX)]));
+    }
+
+    #[test]
+    fn test_interval_insert_range() {
+        let mut s = store(&[(1, 3), (7, 9), (20, 30)]);
+        assert_eq!(s.insert_range(4..=6), 3);
+        assert_eq!(s, store(&[(1, 9), (20, 30)]));
+        assert_eq!(s.insert_range(0..=25), 11);
+        assert_eq!(s, store(&[(0, 30)]));
+        assert_eq!(s.insert_range(40..=u16::MAX), u64::from(u16::MAX) - 39);
+        assert_eq!(s, store(&[(0, 30), (40, u16::MAX)]));
+        assert_eq!(s.insert_range(0..=u16::MAX), 9);
+        assert_eq!(s, IntervalStore::full());
+    }
+
+    #[test]
+    fn test_interval_remove() {
+        let mut s = store(&[(1, 5), (7, 7)]);
+        assert!(!s.remove(6));
+        assert!(s.remove(3));
+        assert_eq!(s, store(&[(1, 2), (4, 5), (7, 7)]));
+        assert!(s.remove(7));
+        assert!(s.remove(1));
+        assert!(s.remove(5));
+        assert_eq!(s, store(&[(2, 2), (4, 4)]));
+    }
+
+    #[test]
+    fn test_interval_remove_range() {
+        let mut s = store(&[(1, 5), (7, 9), (20, u16::MAX)]);
+        assert_eq!(s.remove_range(3..=8), 5);
+        assert_eq!(s, store(&[(1, 2), (9, 9), (20, u16::MAX)]));
+        assert_eq!(s.remove_range(10..=19), 0);
+        assert_eq!(s.remove_range(30..=u16::MAX), u64::from(u16::MAX) - 29);
+        assert_eq!(s, store(&[(1, 2), (9, 9), (20, 29)]));
+    }
+
+    #[test]
+    fn test_interval_flip_range() {
+        let mut s = store(&[(1, 5), (7, 9), (20, 30)]);
+        s.flip_range(3..=8);
+        assert_eq!(s, store(&[(1, 2), (6, 6), (9, 9), (20, 30)]));
+        s.flip_range(10..=19);
+        assert_eq!(s, store(&[(1, 2), (6, 6), (9, 30)]));
+        s.flip_range(0..=u16::MAX);
+        assert_eq!(s, store(&[(0, 0), (3, 5), (7, 8), (31, u16::MAX)]));
+        s.flip_range(1..=2);
+        assert_eq!(s, store(&[(0, 5), (7, 8), (31, u16::MAX)]));
+        s.flip_range(6..=6);
+        assert_eq!(s, store(&[(0, 8), (31, u16::MAX)]));
+    }
+
+    #[test]
+    fn test_interval_remove_smallest_biggest() {
+        let mut s = store(&[(1, 3), (7, 9), (20, 30)]);
+        s.remove_smallest(4);
+        assert_eq!(s, store(&[(8, 9), (20, 30)]));
+        s.remove_biggest(12);
+        assert_eq!(s, store(&[(8, 8)]));
+        s.remove_biggest(1);
+        assert!(s.is_empty());
+    }
+
+    #[test]
+    fn test_interval_len() {
+        let mut s = IntervalStore::new();
+        assert!(s.push(1));
+        assert!(s.push(2));
+        s.push_interval_unchecked(Interval::new(2, 10));
+        s.push_interval_unchecked(Interval::new(20, 29));
+        assert_eq!(s.len(), 20);
+        s.retain(|value| value % 2 == 0);
+        assert_eq!(s.len(), 10);
+        s.flip_range(0..=u16::MAX);
+        assert_eq!(s.len(), (1 << 16) - 10);
+        s.remove_smallest(1 << 16);
+        assert_eq!(s.len(), 0);
+    }
+
+    #[test]
+    fn test_interval_rank_select() {
+        let s = store(&[(1, 3), (7, 9), (20, 30)]);
+        assert_eq!(s.rank(0), 0);
+        assert_eq!(s.rank(2), 2);
+        assert_eq!(s.rank(5), 3);
+        assert_eq!(s.rank(u16::MAX), 17);
+        assert_eq!(s.select(0), Some(1));
+        assert_eq!(s.select(3), Some(7));
+        assert_eq!(s.select(16), Some(30));
+        assert_eq!(s.select(17), None);
+    }
+
+    #[test]
+    fn test_interval_conversions() {
+        let s = store(&[(0, 0), (2, 70), (128, 191), (1000, u16::MAX)]);
+        let array = s.to_array_store();
+        let bitmap = s.to_bitmap_store();
+        assert_eq!(IntervalStore::from_array_store(&array), s);
+        assert_eq!(IntervalStore::from_bitmap_store(&bitmap), s);
+        assert_eq!(IntervalStore::count_runs_array(&array), 4);
+        assert_eq!(IntervalStore::count_runs_bitmap(&bitmap), 4);
+        assert_eq!(IntervalStore::from_bitmap_store(&BitmapStore::new()), IntervalStore::new());
+    }
+
+    #[test]
+    fn test_interval_ops() {
+        let a = store(&[(1, 5), (10, 20), (30, u16::MAX)]);
+        let b = store(&[(0, 2), (6, 9), (15, 35)]);
+        assert_eq!(&a | &b, store(&[(0, u16::MAX)]));
+        assert_eq!(&a & &b, store(&[(1, 2), (15, 20), (30, 35)]));
+        assert_eq!(&a - &b, store(&[(3, 5), (10, 14), (36, u16::MAX)]));
+        assert_eq!(&b - &a, store(&[(0, 0), (6, 9), (21, 29)]));
+        assert_eq!(&a ^ &b, store(&[(0, 0), (3, 14), (21, 29), (36, u16::MAX)]));
+        assert_eq!((&a & &b).len(), a.intersection_len(&b));
+    }
+
+    #[test]
+    fn test_interval_gaps() {
+        let s = store(&[(1, 3), (7, 9)]);
+        assert_eq!(s.gaps().collect::<Vec<_>>(), vec![0..=0, 4..=6, 10..=u16::MAX]);
+        assert_eq!(IntervalStore::full().gaps().count(), 0);
+        assert_eq!(IntervalStore::new().gaps().collect::<Vec<_>>(), vec![0..=u16::MAX]);
+    }
+
+    #[test]
+    fn test_interval_iter() {
+        let s = store(&[(1, 3), (7, 9), (20, 22)]);
+        assert_eq!(values(&s), vec![1, 2, 3, 7, 8, 9, 20, 21, 22]);
+        assert_eq!(s.iter().rev().collect::<Vec<_>>(), vec![22, 21, 20, 9, 8, 7, 3, 2, 1]);
+
+        let mut iter = s.iter();
+        assert_eq!(iter.nth(4), Some(8));
+        assert_eq!(iter.next_back(), Some(22));
+        assert_eq!(iter.len(), 3);
+
+        let mut iter = s.iter();
+        iter.advance_to(5);
+        assert_eq!(iter.len(), 6);
+        assert_eq!(iter.next(), Some(7));
+        iter.advance_back_to(10);
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(9));
+        iter.advance_to(100);
+        assert_eq!(iter.next(), None);
+    }
+}
@@ -1,5 +1,6 @@
 mod array_store;
 mod bitmap_store;
+mod interval_store;
 
 use alloc::vec;
 use core::mem;
@@ -9,10 +10,11 @@ use core::ops::{
 use core::slice;
 
 pub use self::bitmap_store::BITMAP_LENGTH;
-use self::Store::{Array, Bitmap};
+use self::Store::{Array, Bitmap, Run};
 
 pub use self::array_store::ArrayStore;
-pub use self::bitmap_store::{BitmapIter, BitmapStore};
+pub use self::bitmap_store::{BitmapIter, BitmapRanges, BitmapStore};
+pub use self::interval_store::{Interval, IntervalStore, RunIter};
 
 use crate::bitmap::container::ARRAY_LIMIT;
 
@@ -23,6 +25,7 @@ use alloc::boxed::Box;
 pub enum Store {
     Array(ArrayStore),
     Bitmap(BitmapStore),
+    Run(IntervalStore),
 }
 
 #[derive(Clone)]
@@ -31,6 +34,8 @@ pub enum Iter<'a> {
     Vec(vec::IntoIter<u16>),
     BitmapBorrowed(BitmapIter<&'a [u64; BITMAP_LENGTH]>),
     BitmapOwned(BitmapIter<Box<[u64; BITMAP_LENGTH]>>),
+    RunBorrowed(RunIter<&'a [Interval]>),
+    RunOwned(RunIter<vec::Vec<Interval>>),
 }
 
 impl Store {
@@ -84,6 +89,7 @@ impl Store {
         match self {
             Array(vec) => vec.insert(index),
             Bitmap(bits) => bits.insert(index),
+            Run(runs) => runs.insert(index),
         }
     }
 
@@ -96,6 +102,7 @@ impl Store {
         match self {
             Array(vec) => vec.insert_range(range),
             Bitmap(bits) => bits.insert_range(range),
+            Run(runs) => runs.insert_range(range),
         }
     }
 
@@ -106,6 +113,7 @@ impl Store {
         match self {
             Array(vec) => vec.push(index),
             Bitmap(bits) => bits.push(index),
+            Run(runs) => runs.push(index),
         }
     }
 
@@ -120,6 +128,7 @@ impl Store {
         match self {
             Array(vec) => vec.push_unchecked(index),
             Bitmap(bits) => bits.push_unchecked(index),
+            Run(runs) => runs.push_unchecked(index),
         }
     }
 
@@ -127,6 +136,7 @@ impl Store {
         match self {
             Array(vec) => vec.remove(index),
             Bitmap(bits) => bits.remove(index),
+            Run(runs) => runs.remove(index),
         }
     }
 
@@ -138,6 +148,28 @@ impl Store {
         match self {
             Array(vec) => vec.remove_range(range),
             Bitmap(bits) => bits.remove_range(range),
+            Run(runs) => runs.remove_range(range),
+        }
+    }
+
+    pub fn flip_range(&mut self, range: RangeInclusive<u16>) {
+        if range.is_empty() {
+            return;
+        }
+
+        match self {
+            Array(vec) => vec.flip_range(range),
+            Bitmap(bits) => bits.flip_range(range),
+            Run(runs) => runs.flip_range(range),
+        }
+    }
+
+    /// Retains only the elements specified by the predicate, visiting them in order.
+    pub fn retain(&mut self, f: impl FnMut(u16) -> bool) {
+        match self {
+            Array(vec) => vec.retain(f),
+            Bitmap(bits) => bits.retain(f),
+            Run(runs) => runs.retain(f),
         }
     }
 
@@ -145,6 +177,7 @@ impl Store {
         match self {
             Array(vec) => vec.remove_smallest(index),
             Bitmap(bits) => bits.remove_smallest(index),
+            Run(runs) => runs.remove_smallest(index),
         }
     }
 
@@ -152,6 +185,7 @@ impl Store {
         match self {
             Array(vec) => vec.remove_biggest(index),
             Bitmap(bits) => bits.remove_biggest(index),
+            Run(runs) => runs.remove_biggest(index),
         }
     }
 
@@ -159,6 +193,7 @@ impl Store {
         match self {
             Array(vec) => vec.contains(index),
             Bitmap(bits) => bits.contains(index),
+            Run(runs) => runs.contains(index),
         }
     }
 
@@ -166,6 +201,7 @@ impl Store {
         match self {
             Array(vec) => vec.contains_range(range),
             Bitmap(bits) => bits.contains_range(range),
+            Run(runs) => runs.contains_range(range),
         }
     }
 
@@ -180,6 +216,9 @@ impl Store {
             (Array(vec), Bitmap(bits)) | (Bitmap(bits), Array(vec)) => {
                 vec.iter().all(|&i| !bits.contains(i))
             }
+            (Run(runs1), Run(runs2)) => runs1.is_disjoint(runs2),
+            (Run(runs), Array(vec)) | (Array(vec), Run(runs)) => runs.is_disjoint_array(vec),
+            (Run(runs), Bitmap(bits)) | (Bitmap(bits), Run(runs)) => runs.is_disjoint_bitmap(bits),
         }
     }
 
@@ -189,6 +228,11 @@ impl Store {
             (Bitmap(bits1), Bitmap(bits2)) => bits1.is_subset(bits2),
             (Array(vec), Bitmap(bits)) => vec.iter().all(|&i| bits.contains(i)),
             (Bitmap(..), &Array(..)) => false,
+            (Run(runs1), Run(runs2)) => runs1.is_subset(runs2),
+            (Run(runs), Array(vec)) => runs.is_subset_array(vec),
+            (Run(runs), Bitmap(bits)) => runs.is_subset_bitmap(bits),
+            (Array(vec), Run(runs)) => runs.is_superset_array(vec),
+            (Bitmap(bits), Run(runs)) => runs.is_superset_bitmap(bits),
         }
     }
 
@@ -198,6 +242,11 @@ impl Store {
             (Bitmap(bits1), Bitmap(bits2)) => bits1.intersection_len_bitmap(bits2),
             (Array(vec), Bitmap(bits)) => bits.intersection_len_array(vec),
             (Bitmap(bits), Array(vec)) => bits.intersection_len_array(vec),
+            (Run(runs1), Run(runs2)) => runs1.intersection_len(runs2),
+            (Run(runs), Array(vec)) | (Array(vec), Run(runs)) => runs.intersection_len_array(vec),
+            (Run(runs), Bitmap(bits)) | (Bitmap(bits), Run(runs)) => {
+                runs.intersection_len_bitmap(bits)
+            }
         }
     }
 
@@ -205,6 +254,7 @@ impl Store {
         match self {
             Array(vec) => vec.len(),
             Bitmap(bits) => bits.len(),
+            Run(runs) => runs.len(),
         }
     }
 
@@ -212,6 +262,7 @@ impl Store {
         match self {
             Array(vec) => vec.is_empty(),
             Bitmap(bits) => bits.is_empty(),
+            Run(runs) => runs.is_empty(),
         }
     }
 
@@ -219,6 +270,7 @@ impl Store {
         match self {
             Array(vec) => vec.min(),
             Bitmap(bits) => bits.min(),
+            Run(runs) => runs.min(),
         }
     }
 
@@ -227,6 +279,7 @@ impl Store {
         match self {
             Array(vec) => vec.max(),
             Bitmap(bits) => bits.max(),
+            Run(runs) => runs.max(),
         }
     }
 
@@ -234,6 +287,7 @@ impl Store {
         match self {
             Array(vec) => vec.rank(index),
             Bitmap(bits) => bits.rank(index),
+            Run(runs) => runs.rank(index),
         }
     }
 
@@ -241,6 +295,35 @@ impl Store {
         match self {
             Array(vec) => vec.select(n),
             Bitmap(bits) => bits.select(n),
+            Run(runs) => runs.select(n),
+        }
+    }
+
+    /// Adds the offset to every value, returns the values that stay in the store
+    /// and the ones that overflow, wrapped around, into the next one.
+    pub fn add_offset(&self, offset: u16) -> (Store, Store) {
+        match self {
+            Array(vec) => {
+                let (low, high) = vec.add_offset(offset);
+                (Array(low), Array(high))
+            }
+            Bitmap(bits) => {
+                let (low, high) = bits.add_offset(offset);
+                (Bitmap(low), Bitmap(high))
+            }
+            Run(runs) => {
+                let (low, high) = runs.add_offset(offset);
+                (Run(low), Run(high))
+            }
+        }
+    }
+
+    /// Iterates over the maximal runs of consecutive values of the store.
+    pub fn ranges(&self) -> Ranges<'_> {
+        match self {
+            Array(vec) => Ranges::Array(vec.as_slice()),
+            Bitmap(bits) => Ranges::Bitmap(bits.ranges()),
+            Run(runs) => Ranges::Run(runs.as_slice().iter()),
         }
     }
 
@@ -248,6 +331,7 @@ impl Store {
         match self {
             Array(arr) => Bitmap(arr.to_bitmap_store()),
             Bitmap(_) => self.clone(),
+            Run(runs) => Bitmap(runs.to_bitmap_store()),
         }
     }
 }
@@ -264,17 +348,21 @@ impl BitOr<&Store> for &Store {
     fn bitor(self, rhs: &Store) -> Store {
         match (self, rhs) {
             (Array(vec1), Array(vec2)) => Array(BitOr::bitor(vec1, vec2)),
+            (Run(runs1), Run(runs2)) => Run(BitOr::bitor(runs1, runs2)),
+            (Run(runs), Array(vec)) | (Array(vec), Run(runs)) => {
+                Run(BitOr::bitor(runs, &IntervalStore::from_array_store(vec)))
+            }
             (&Bitmap(..), &Array(..)) => {
                 let mut lhs = self.clone();
                 BitOrAssign::bitor_assign(&mut lhs, rhs);
                 lhs
             }
-            (&Bitmap(..), &Bitmap(..)) => {
+            (&Bitmap(..), &Bitmap(..)) | (&Bitmap(..), &Run(..)) => {
                 let mut lhs = self.clone();
                 BitOrAssign::bitor_assign(&mut lhs, rhs);
                 lhs
             }
-            (&Array(..), &Bitmap(..)) => {
+            (&Array(..), &Bitmap(..)) | (&Run(..), &Bitmap(..)) => {
                 let mut rhs = rhs.clone();
                 BitOrAssign::bitor_assign(&mut rhs, self);
                 rhs
@@ -295,7 +383,17 @@ impl BitOrAssign<Store> for Store {
             (&mut Bitmap(ref mut bits1), &mut Bitmap(ref bits2)) => {
                 BitOrAssign::bitor_assign(bits1, bits2);
             }
-            (this @ &mut Array(..), &mut Bitmap(..)) => {
+            (&mut Bitmap(ref mut bits1), &mut Run(ref runs2)) => {
+                BitOrAssign::bitor_assign(bits1, runs2);
+            }
+            (&mut Run(ref mut runs1), &mut Run(ref runs2)) => {
+                *runs1 = BitOr::bitor(&*runs1, runs2);
+            }
+            (&mut Run(ref mut runs1), &mut Array(ref vec2)) => {
+                *runs1 = BitOr::bitor(&*runs1, &IntervalStore::from_array_store(vec2));
+            }
+            (this @ (&mut Array(..) | &mut Run(..)), &mut Bitmap(..))
+            | (this @ &mut Array(..), &mut Run(..)) => {
                 mem::swap(this, &mut rhs);
                 BitOrAssign::bitor_assign(this, rhs);
             }
@@ -316,8 +414,18 @@ impl BitOrAssign<&Store> for Store {
             (&mut Bitmap(ref mut bits1), Bitmap(bits2)) => {
                 BitOrAssign::bitor_assign(bits1, bits2);
             }
-            (this @ &mut Array(..), Bitmap(bits2)) => {
-                let mut lhs: Store = Bitmap(bits2.clone());
+            (&mut Bitmap(ref mut bits1), Run(runs2)) => {
+                BitOrAssign::bitor_assign(bits1, runs2);
+            }
+            (&mut Run(ref mut runs1), Run(runs2)) => {
+                *runs1 = BitOr::bitor(&*runs1, runs2);
+            }
+            (&mut Run(ref mut runs1), Array(vec2)) => {
+                *runs1 = BitOr::bitor(&*runs1, &IntervalStore::from_array_store(vec2));
+            }
+            (this @ (&mut Array(..) | &mut Run(..)), Bitmap(..))
+            | (this @ &mut Array(..), Run(..)) => {
+                let mut lhs: Store = rhs.clone();
                 BitOrAssign::bitor_assign(&mut lhs, &*this);
                 *this = lhs;
             }
@@ -331,7 +439,8 @@ impl BitAnd<&Store> for &Store {
     fn bitand(self, rhs: &Store) -> Store {
         match (self, rhs) {
             (Array(vec1), Array(vec2)) => Array(BitAnd::bitand(vec1, vec2)),
-            (&Bitmap(..), &Array(..)) => {
+            (Run(runs1), Run(runs2)) => Run(BitAnd::bitand(runs1, runs2)),
+            (&Bitmap(..), &Array(..)) | (&Run(..), &Array(..)) | (&Run(..), &Bitmap(..)) => {
                 let mut rhs = rhs.clone();
                 BitAndAssign::bitand_assign(&mut rhs, self);
                 rhs
@@ -361,7 +470,17 @@ impl BitAndAssign<Store> for Store {
             (&mut Array(ref mut vec1), &mut Bitmap(ref bits2)) => {
                 BitAndAssign::bitand_assign(vec1, bits2);
             }
-            (this @ &mut Bitmap(..), &mut Array(..)) => {
+            (&mut Run(ref mut runs1), &mut Run(ref runs2)) => {
+                *runs1 = BitAnd::bitand(&*runs1, runs2);
+            }
+            (&mut Array(ref mut vec1), &mut Run(ref runs2)) => {
+                BitAndAssign::bitand_assign(vec1, runs2);
+            }
+            (&mut Bitmap(ref mut bits1), &mut Run(ref runs2)) => {
+                BitAndAssign::bitand_assign(bits1, runs2);
+            }
+            (this @ &mut Bitmap(..), &mut Array(..))
+            | (this @ &mut Run(..), &mut Array(..) | &mut Bitmap(..)) => {
                 mem::swap(this, &mut rhs);
                 BitAndAssign::bitand_assign(this, rhs);
             }
@@ -389,7 +508,17 @@ impl BitAndAssign<&Store> for Store {
             (&mut Array(ref mut vec1), Bitmap(bits2)) => {
                 BitAndAssign::bitand_assign(vec1, bits2);
             }
-            (this @ &mut Bitmap(..), &Array(..)) => {
+            (&mut Run(ref mut runs1), Run(runs2)) => {
+                *runs1 = BitAnd::bitand(&*runs1, runs2);
+            }
+            (&mut Array(ref mut vec1), Run(runs2)) => {
+                BitAndAssign::bitand_assign(vec1, runs2);
+            }
+            (&mut Bitmap(ref mut bits1), Run(runs2)) => {
+                BitAndAssign::bitand_assign(bits1, runs2);
+            }
+            (this @ &mut Bitmap(..), &Array(..))
+            | (this @ &mut Run(..), &Array(..) | &Bitmap(..)) => {
                 let mut new = rhs.clone();
                 BitAndAssign::bitand_assign(&mut new, &*this);
                 *this = new;
@@ -404,6 +533,10 @@ impl Sub<&Store> for &Store {
     fn sub(self, rhs: &Store) -> Store {
         match (self, rhs) {
             (Array(vec1), Array(vec2)) => Array(Sub::sub(vec1, vec2)),
+            (Run(runs1), Run(runs2)) => Run(Sub::sub(runs1, runs2)),
+            (Run(runs1), Array(vec2)) => {
+                Run(Sub::sub(runs1, &IntervalStore::from_array_store(vec2)))
+            }
             _ => {
                 let mut lhs = self.clone();
                 SubAssign::sub_assign(&mut lhs, rhs);
@@ -428,6 +561,23 @@ impl SubAssign<&Store> for Store {
             (&mut Array(ref mut vec1), Bitmap(bits2)) => {
                 SubAssign::sub_assign(vec1, bits2);
             }
+            (&mut Array(ref mut vec1), Run(runs2)) => {
+                SubAssign::sub_assign(vec1, runs2);
+            }
+            (&mut Bitmap(ref mut bits1), Run(runs2)) => {
+                SubAssign::sub_assign(bits1, runs2);
+            }
+            (&mut Run(ref mut runs1), Run(runs2)) => {
+                *runs1 = Sub::sub(&*runs1, runs2);
+            }
+            (&mut Run(ref mut runs1), Array(vec2)) => {
+                *runs1 = Sub::sub(&*runs1, &IntervalStore::from_array_store(vec2));
+            }
+            (this @ &mut Run(..), Bitmap(..)) => {
+                let mut lhs = this.to_bitmap();
+                SubAssign::sub_assign(&mut lhs, rhs);
+                *this = lhs;
+            }
         }
     }
 }
@@ -438,7 +588,11 @@ impl BitXor<&Store> for &Store {
     fn bitxor(self, rhs: &Store) -> Store {
         match (self, rhs) {
             (Array(vec1), Array(vec2)) => Array(BitXor::bitxor(vec1, vec2)),
-            (&Array(..), &Bitmap(..)) => {
+            (Run(runs1), Run(runs2)) => Run(BitXor::bitxor(runs1, runs2)),
+            (Run(runs), Array(vec)) | (Array(vec), Run(runs)) => {
+                Run(BitXor::bitxor(runs, &IntervalStore::from_array_store(vec)))
+            }
+            (&Array(..), &Bitmap(..)) | (&Run(..), &Bitmap(..)) => {
                 let mut lhs = rhs.clone();
                 BitXorAssign::bitxor_assign(&mut lhs, self);
                 lhs
@@ -464,7 +618,17 @@ impl BitXorAssign<Store> for Store {
             (&mut Bitmap(ref mut bits1), &mut Bitmap(ref bits2)) => {
                 BitXorAssign::bitxor_assign(bits1, bits2);
             }
-            (this @ &mut Array(..), &mut Bitmap(..)) => {
+            (&mut Bitmap(ref mut bits1), &mut Run(ref runs2)) => {
+                BitXorAssign::bitxor_assign(bits1, runs2);
+            }
+            (&mut Run(ref mut runs1), &mut Run(ref runs2)) => {
+                *runs1 = BitXor::bitxor(&*runs1, runs2);
+            }
+            (&mut Run(ref mut runs1), &mut Array(ref vec2)) => {
+                *runs1 = BitXor::bitxor(&*runs1, &IntervalStore::from_array_store(vec2));
+            }
+            (this @ (&mut Array(..) | &mut Run(..)), &mut Bitmap(..))
+            | (this @ &mut Array(..), &mut Run(..)) => {
                 mem::swap(this, &mut rhs);
                 BitXorAssign::bitxor_assign(this, rhs);
             }
@@ -485,8 +649,18 @@ impl BitXorAssign<&Store> for Store {
             (&mut Bitmap(ref mut bits1), Bitmap(bits2)) => {
                 BitXorAssign::bitxor_assign(bits1, bits2);
             }
-            (this @ &mut Array(..), Bitmap(bits2)) => {
-                let mut lhs: Store = Bitmap(bits2.clone());
+            (&mut Bitmap(ref mut bits1), Run(runs2)) => {
+                BitXorAssign::bitxor_assign(bits1, runs2);
+            }
+            (&mut Run(ref mut runs1), Run(runs2)) => {
+                *runs1 = BitXor::bitxor(&*runs1, runs2);
+            }
+            (&mut Run(ref mut runs1), Array(vec2)) => {
+                *runs1 = BitXor::bitxor(&*runs1, &IntervalStore::from_array_store(vec2));
+            }
+            (this @ (&mut Array(..) | &mut Run(..)), Bitmap(..))
+            | (this @ &mut Array(..), Run(..)) => {
+                let mut lhs: Store = rhs.clone();
                 BitXorAssign::bitxor_assign(&mut lhs, &*this);
                 *this = lhs;
             }
@@ -501,6 +675,7 @@ impl<'a> IntoIterator for &'a Store {
         match self {
             Array(vec) => Iter::Array(vec.iter()),
             Bitmap(bits) => Iter::BitmapBorrowed(bits.iter()),
+            Run(runs) => Iter::RunBorrowed(runs.iter()),
         }
     }
 }
@@ -512,6 +687,7 @@ impl IntoIterator for Store {
         match self {
             Array(vec) => Iter::Vec(vec.into_iter()),
             Bitmap(bits) => Iter::BitmapOwned(bits.into_iter()),
+            Run(runs) => Iter::RunOwned(runs.into_iter()),
         }
     }
 }
@@ -524,12 +700,49 @@ impl PartialEq for Store {
                 bits1.len() == bits2.len()
                     && bits1.iter().zip(bits2.iter()).all(|(i1, i2)| i1 == i2)
             }
+            (Run(runs1), Run(runs2)) => runs1 == runs2,
+            // A run store may hold the same values as an array or a bitmap store.
+            (Run(runs), other) | (other, Run(runs)) => {
+                runs.len() == other.len() && runs.iter().eq(other)
+            }
             _ => false,
         }
     }
 }
 
 impl Iter<'_> {
+    /// Writes the next values of the iterator, combined with the `high` bits, into `dst`
+    /// and returns the number of values written.
+    pub(crate) fn next_many(&mut self, high: u32, dst: &mut [u32]) -> usize {
+        fn copy_joined(values: &[u16], high: u32, dst: &mut [u32]) -> usize {
+            for (slot, &index) in dst.iter_mut().zip(values) {
+                *slot = high | u32::from(index);
+            }
+            values.len().min(dst.len())
+        }
+
+        match self {
+            Iter::Array(inner) => {
+                let n = copy_joined(inner.as_slice(), high, dst);
+                if let Some(nth) = n.checked_sub(1) {
+                    inner.nth(nth);
+                }
+                n
+            }
+            Iter::Vec(inner) => {
+                let n = copy_joined(inner.as_slice(), high, dst);
+                if let Some(nth) = n.checked_sub(1) {
+                    inner.nth(nth);
+                }
+                n
+            }
+            Iter::BitmapBorrowed(inner) => inner.next_many(high, dst),
+            Iter::BitmapOwned(inner) => inner.next_many(high, dst),
+            Iter::RunBorrowed(inner) => inner.next_many(high, dst),
+            Iter::RunOwned(inner) => inner.next_many(high, dst),
+        }
+    }
+
     /// Advance the iterator to the first value greater than or equal to `n`.
     pub(crate) fn advance_to(&mut self, n: u16) {
         match self {
@@ -547,6 +760,8 @@ impl Iter<'_> {
             }
             Iter::BitmapBorrowed(inner) => inner.advance_to(n),
             Iter::BitmapOwned(inner) => inner.advance_to(n),
+            Iter::RunBorrowed(inner) => inner.advance_to(n),
+            Iter::RunOwned(inner) => inner.advance_to(n),
         }
     }
 
@@ -570,6 +785,8 @@ impl Iter<'_> {
             }
             Iter::BitmapBorrowed(inner) => inner.advance_back_to(n),
             Iter::BitmapOwned(inner) => inner.advance_back_to(n),
+            Iter::RunBorrowed(inner) => inner.advance_back_to(n),
+            Iter::RunOwned(inner) => inner.advance_back_to(n),
         }
     }
 }
@@ -583,6 +800,8 @@ impl Iterator for Iter<'_> {
             Iter::Vec(inner) => inner.next(),
             Iter::BitmapBorrowed(inner) => inner.next(),
             Iter::BitmapOwned(inner) => inner.next(),
+            Iter::RunBorrowed(inner) => inner.next(),
+            Iter::RunOwned(inner) => inner.next(),
         }
     }
 
@@ -592,6 +811,8 @@ impl Iterator for Iter<'_> {
             Iter::Vec(inner) => inner.size_hint(),
             Iter::BitmapBorrowed(inner) => inner.size_hint(),
             Iter::BitmapOwned(inner) => inner.size_hint(),
+            Iter::RunBorrowed(inner) => inner.size_hint(),
+            Iter::RunOwned(inner) => inner.size_hint(),
         }
     }
 
@@ -604,6 +825,8 @@ impl Iterator for Iter<'_> {
             Iter::Vec(inner) => inner.count(),
             Iter::BitmapBorrowed(inner) => inner.count(),
             Iter::BitmapOwned(inner) => inner.count(),
+            Iter::RunBorrowed(inner) => inner.count(),
+            Iter::RunOwned(inner) => inner.count(),
         }
     }
 
@@ -613,6 +836,8 @@ impl Iterator for Iter<'_> {
             Iter::Vec(inner) => inner.nth(n),
             Iter::BitmapBorrowed(inner) => inner.nth(n),
             Iter::BitmapOwned(inner) => inner.nth(n),
+            Iter::RunBorrowed(inner) => inner.nth(n),
+            Iter::RunOwned(inner) => inner.nth(n),
         }
     }
 }
@@ -624,8 +849,52 @@ impl DoubleEndedIterator for Iter<'_> {
             Iter::Vec(inner) => inner.next_back(),
             Iter::BitmapBorrowed(inner) => inner.next_back(),
             Iter::BitmapOwned(inner) => inner.next_back(),
+            Iter::RunBorrowed(inner) => inner.next_back(),
+            Iter::RunOwned(inner) => inner.next_back(),
         }
     }
 }
 
 impl ExactSizeIterator for Iter<'_> {}
+
+#[derive(Clone)]
+pub enum Ranges<'a> {
+    Array(&'a [u16]),
+    Bitmap(BitmapRanges<'a>),
+    Run(slice::Iter<'a, Interval>),
+}
+
+impl Iterator for Ranges<'_> {
+    type Item = RangeInclusive<u16>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u16>> {
+        match self {
+            Ranges::Array(values) => {
+                let (&start, rest) = values.split_first()?;
+                // Values are sorted and unique, a run ends where they stop being consecutive
+                let next = u32::from(start) + 1;
+                let len = rest.iter().zip(next..).take_while(|(&v, i)| u32::from(v) == *i).count();
+                *values = &rest[len..];
+                Some(start..=start + len as u16)
+            }
+            Ranges::Bitmap(inner) => inner.next(),
+            Ranges::Run(inner) => inner.next().map(Interval::range),
+        }
+    }
+}
+
+impl DoubleEndedIterator for Ranges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u16>> {
+        match self {
+            Ranges::Array(values) => {
+                let (&end, rest) = values.split_last()?;
+                let len =
+                    rest.iter().rev().zip((0..end).rev()).take_while(|(&v, i)| v == *i).count();
+                *values = &rest[..rest.len() - len];
+                Some(end - len as u16..=end)
+            }
+            Ranges::Bitmap(inner) => inner.next_back(),
+            Ranges::Run(inner) => inner.next_back().map(Interval::range),
+        }
+    }
+}
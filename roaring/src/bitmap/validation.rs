@@ -0,0 +1,360 @@
+use core::fmt;
+
+use super::container::ARRAY_LIMIT;
+use super::serialization::{
+    DeserializeError, DESCRIPTION_BYTES, NO_OFFSET_THRESHOLD, OFFSET_BYTES, SERIAL_COOKIE,
+    SERIAL_COOKIE_NO_RUNCONTAINER,
+};
+use super::store::BITMAP_LENGTH;
+use super::view::{
+    array_value, cardinality, is_run, read_bytes, read_u16_unchecked, read_u32_unchecked,
+    read_u64_unchecked, BITMAP_BYTES, RUN_BYTES,
+};
+use crate::RoaringBitmap;
+
+/// A report on the validity of a serialized bitmap,
+/// see [`RoaringBitmap::validate_serialized`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct ValidationReport {
+    /// Number of bytes taken by the serialized bitmap, `None` if its end could not be reached
+    pub serialized_size: Option<usize>,
+    /// Number of containers announced by the header
+    pub n_containers: u32,
+    /// Number of array containers that were checked
+    pub n_array_containers: u32,
+    /// Number of run containers that were checked
+    pub n_run_containers: u32,
+    /// Number of bitset containers that were checked
+    pub n_bitset_containers: u32,
+    /// Number of values announced by the container descriptions
+    pub cardinality: u64,
+    /// The problems found, in the order of the bytes
+    pub problems: Vec<ValidationProblem>,
+}
+
+impl ValidationReport {
+    /// Returns `true` if no problem was found, which means that the bytes can
+    /// be deserialized and viewed without any error.
+    pub fn is_valid(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// A problem found in a serialized bitmap, see [`ValidationReport`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ValidationProblem {
+    /// The bytes end before the bitmap does, `position` is where reading failed
+    Truncated {
+        /// The byte position of the missing data
+        position: usize,
+    },
+    /// The serialized offset of a container does not point to its data
+    InvalidOffset {
+        /// The key of the container
+        key: u16,
+        /// The position of the data of the container
+        expected: usize,
+        /// The serialized offset
+        actual: u32,
+    },
+    /// A problem that also makes [`RoaringBitmap::deserialize_from`] fail
+    Invalid(DeserializeError),
+}
+
+impl fmt::Display for ValidationProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationProblem::Truncated { position } => {
+                write!(f, "unexpected end of bytes at position {position}")
+            }
+            ValidationProblem::InvalidOffset { key, expected, actual } => {
+                write!(f, "offset of container {key} is {actual} but its data is at {expected}")
+            }
+            ValidationProblem::Invalid(error) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ValidationProblem {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ValidationProblem::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<DeserializeError> for ValidationProblem {
+    fn from(error: DeserializeError) -> Self {
+        ValidationProblem::Invalid(error)
+    }
+}
+
+impl RoaringBitmap {
+    /// Checks bytes in the portable serialization format without deserializing them.
+    ///
+    /// The whole bitmap is walked without allocating any container: the header,
+    /// the ordering of the keys, the offsets, the sorting of the arrays, the runs
+    /// and the cardinality of every container are checked. Trailing bytes after
+    /// the bitmap are ignored, see [`ValidationReport::serialized_size`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::bitmap::{DeserializeError, ValidationProblem};
+    /// use roaring::RoaringBitmap;
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// let report = RoaringBitmap::validate_serialized(&bytes);
+    /// assert!(report.is_valid());
+    /// assert_eq!(report.serialized_size, Some(bytes.len()));
+    /// assert_eq!(report.cardinality, 3);
+    ///
+    /// // Replace the value 2 by 1
+    /// bytes[18] = 1;
+    /// let report = RoaringBitmap::validate_serialized(&bytes);
+    /// assert!(matches!(
+    ///     report.problems[..],
+    ///     [ValidationProblem::Invalid(DeserializeError::UnsortedArray { key: 0 })]
+    /// ));
+    /// ```
+    pub fn validate_serialized(bytes: &[u8]) -> ValidationReport {
+        let mut report = ValidationReport {
+            serialized_size: None,
+            n_containers: 0,
+            n_array_containers: 0,
+            n_run_containers: 0,
+            n_bitset_containers: 0,
+            cardinality: 0,
+            problems: Vec::new(),
+        };
+
+        match validate(bytes, &mut report) {
+            Ok(size) => report.serialized_size = Some(size),
+            Err(problem) => report.problems.push(problem),
+        }
+
+        report
+    }
+}
+
+/// Walks the bitmap, recording the recoverable problems in the report,
+/// and returns its size or the problem that prevented reaching its end.
+fn validate(bytes: &[u8], report: &mut ValidationReport) -> Result<usize, ValidationProblem> {
+    let cookie = read_u32_unchecked(read(bytes, 0, 4)?, 0);
+    let (size, has_offsets, run_flags) = if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+        (read_u32_unchecked(read(bytes, 4, 4)?, 0) as usize, true, &bytes[..0])
+    } else if (cookie as u16) == SERIAL_COOKIE {
+        let size = ((cookie >> 16) + 1) as usize;
+        (size, size >= NO_OFFSET_THRESHOLD, read(bytes, 4, (size + 7) / 8)?)
+    } else {
+        return Err(DeserializeError::UnknownCookie(cookie).into());
+    };
+
+    if size > u16::MAX as usize + 1 {
+        return Err(DeserializeError::TooManyContainers(size).into());
+    }
+    report.n_containers = size as u32;
+
+    let mut position = if run_flags.is_empty() { 8 } else { 4 + run_flags.len() };
+    let descriptions = read(bytes, position, size * DESCRIPTION_BYTES)?;
+    position += descriptions.len();
+
+    let offsets = if has_offsets {
+        let offsets = read(bytes, position, size * OFFSET_BYTES)?;
+        position += offsets.len();
+        Some(offsets)
+    } else {
+        None
+    };
+
+    let mut previous_key = None;
+    for i in 0..size {
+        let key = read_u16_unchecked(descriptions, i * DESCRIPTION_BYTES);
+        let expected = cardinality(descriptions, i);
+        report.cardinality += expected;
+
+        if previous_key.map_or(false, |previous| previous >= key) {
+            report.problems.push(DeserializeError::UnsortedKeys { key }.into());
+        }
+        previous_key = Some(key);
+
+        if let Some(offsets) = offsets {
+            let actual = read_u32_unchecked(offsets, i * OFFSET_BYTES);
+            if actual as usize != position {
+                report.problems.push(ValidationProblem::InvalidOffset {
+                    key,
+                    expected: position,
+                    actual,
+                });
+            }
+        }
+
+        let actual = if is_run(run_flags, i) {
+            report.n_run_containers += 1;
+            let n_runs = read_u16_unchecked(read(bytes, position, 2)?, 0) as usize;
+            let runs = read(bytes, position + 2, n_runs * RUN_BYTES)?;
+            position += 2 + runs.len();
+            match runs_cardinality(runs) {
+                Some(actual) => actual,
+                None => {
+                    report.problems.push(DeserializeError::InvalidRun { key }.into());
+                    continue;
+                }
+            }
+        } else if expected <= ARRAY_LIMIT {
+            report.n_array_containers += 1;
+            let values = read(bytes, position, expected as usize * 2)?;
+            position += values.len();
+            if (1..expected as usize).any(|i| array_value(values, i - 1) >= array_value(values, i))
+            {
+                report.problems.push(DeserializeError::UnsortedArray { key }.into());
+            }
+            expected
+        } else {
+            report.n_bitset_containers += 1;
+            let words = read(bytes, position, BITMAP_BYTES)?;
+            position += words.len();
+            (0..BITMAP_LENGTH)
+                .map(|i| u64::from(read_u64_unchecked(words, i * 8).count_ones()))
+                .sum()
+        };
+
+        if actual != expected {
+            report
+                .problems
+                .push(DeserializeError::CardinalityMismatch { key, expected, actual }.into());
+        }
+    }
+
+    Ok(position)
+}
+
+/// Returns the number of values in the runs, or `None` if they are invalid.
+fn runs_cardinality(runs: &[u8]) -> Option<u64> {
+    let mut cardinality = 0;
+    let mut previous_end: Option<u16> = None;
+    for i in 0..runs.len() / RUN_BYTES {
+        let start = read_u16_unchecked(runs, i * RUN_BYTES);
+        let len = read_u16_unchecked(runs, i * RUN_BYTES + 2);
+        let end = start.checked_add(len)?;
+        if previous_end.map_or(false, |previous| u32::from(start) <= u32::from(previous) + 1) {
+            return None;
+        }
+        previous_end = Some(end);
+        cardinality += u64::from(len) + 1;
+    }
+    Some(cardinality)
+}
+
+fn read(bytes: &[u8], position: usize, len: usize) -> Result<&[u8], ValidationProblem> {
+    read_bytes(bytes, position, len).map_err(|_| ValidationProblem::Truncated { position })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationProblem::{self, Invalid, InvalidOffset};
+    use crate::bitmap::DeserializeError::{
+        CardinalityMismatch, InvalidRun, TooManyContainers, UnknownCookie, UnsortedArray,
+        UnsortedKeys,
+    };
+    use crate::RoaringBitmap;
+    use proptest::prelude::*;
+
+    fn serialize(bitmap: &RoaringBitmap) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        bytes
+    }
+
+    proptest! {
+        #[test]
+        fn serialized_bitmaps_are_valid(
+            mut bitmap in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+        ) {
+            if optimize {
+                bitmap.optimize();
+            }
+            let mut bytes = serialize(&bitmap);
+            bytes.extend_from_slice(&[1, 2, 3]);
+
+            let report = RoaringBitmap::validate_serialized(&bytes);
+            let statistics = bitmap.statistics();
+            prop_assert!(report.is_valid(), "{:?}", report.problems);
+            prop_assert_eq!(report.serialized_size, Some(bitmap.serialized_size()));
+            prop_assert_eq!(report.cardinality, bitmap.len());
+            prop_assert_eq!(report.n_containers, statistics.n_containers);
+            prop_assert_eq!(report.n_array_containers, statistics.n_array_containers);
+            prop_assert_eq!(report.n_run_containers, statistics.n_run_containers);
+            prop_assert_eq!(report.n_bitset_containers, statistics.n_bitset_containers);
+        }
+    }
+
+    #[test]
+    fn truncated_bytes_are_invalid() {
+        let mut bitmap: RoaringBitmap = (0..10).chain(100_000..200_000).collect();
+        for optimize in [false, true] {
+            if optimize {
+                bitmap.optimize();
+            }
+            let bytes = serialize(&bitmap);
+            for len in 0..bytes.len() {
+                let report = RoaringBitmap::validate_serialized(&bytes[..len]);
+                assert!(matches!(report.problems[..], [ValidationProblem::Truncated { .. }]));
+                assert_eq!(report.serialized_size, None);
+            }
+        }
+    }
+
+    #[test]
+    fn corrupted_bytes_are_invalid() {
+        let problems = |bytes: &[u8]| RoaringBitmap::validate_serialized(bytes).problems;
+
+        let bytes = serialize(&(1..4).collect());
+        assert!(matches!(problems(&[0, 0, 0, 0])[..], [Invalid(UnknownCookie(0))]));
+
+        let mut too_many = bytes.clone();
+        too_many[4..8].copy_from_slice(&(u16::MAX as u32 + 2).to_le_bytes());
+        assert!(matches!(problems(&too_many)[..], [Invalid(TooManyContainers(65537))]));
+
+        let mut unsorted = bytes.clone();
+        unsorted[18] = 1;
+        assert!(matches!(problems(&unsorted)[..], [Invalid(UnsortedArray { key: 0 })]));
+
+        let bytes = serialize(&[1, 65536 + 1].into_iter().collect());
+        let mut unsorted_keys = bytes.clone();
+        unsorted_keys[12] = 0;
+        assert!(matches!(problems(&unsorted_keys)[..], [Invalid(UnsortedKeys { key: 0 })]));
+
+        let mut invalid_offset = bytes;
+        invalid_offset[16] += 1;
+        assert!(matches!(
+            problems(&invalid_offset)[..],
+            [InvalidOffset { key: 0, expected: 24, actual: 25 }]
+        ));
+
+        let mut bytes = serialize(&(0..5000).collect());
+        bytes[10] -= 1;
+        assert!(matches!(
+            problems(&bytes)[..],
+            [Invalid(CardinalityMismatch { key: 0, expected: 4999, actual: 5000 })]
+        ));
+
+        let mut bitmap: RoaringBitmap = (1..4).chain(10..20).collect();
+        bitmap.optimize();
+        let bytes = serialize(&bitmap);
+        let mut overlapping = bytes.clone();
+        overlapping[15] = 3;
+        assert!(matches!(problems(&overlapping)[..], [Invalid(InvalidRun { key: 0 })]));
+
+        let mut overflowing = bytes;
+        overflowing[17..19].copy_from_slice(&[0xFF, 0xFF]);
+        assert!(matches!(problems(&overflowing)[..], [Invalid(InvalidRun { key: 0 })]));
+    }
+}
@@ -0,0 +1,1523 @@
+use core::cmp::Ordering;
+use core::iter::FusedIterator;
+use core::ops::{BitAndAssign, BitOrAssign, BitXorAssign, Range, RangeBounds, SubAssign};
+use std::io;
+
+use super::container::{Container, ARRAY_LIMIT};
+use super::serialization::{
+    DeserializeError, DESCRIPTION_BYTES, FROZEN_ARRAY_TYPE, FROZEN_BITSET_TYPE, FROZEN_COOKIE,
+    FROZEN_RUN_TYPE, NO_OFFSET_THRESHOLD, OFFSET_BYTES, SERIAL_COOKIE,
+    SERIAL_COOKIE_NO_RUNCONTAINER,
+};
+use super::store::{ArrayStore, BitmapStore, Interval, IntervalStore, Store, BITMAP_LENGTH};
+use super::util;
+use crate::RoaringBitmap;
+
+pub(super) const BITMAP_BYTES: usize = BITMAP_LENGTH * 8;
+pub(super) const RUN_BYTES: usize = 4;
+
+/// A read-only view over a bitmap serialized in [the standard Roaring on-disk format][format]
+/// or in the CRoaring frozen format.
+///
+/// The header, descriptions and offsets are parsed once when the view is created, every
+/// query then reads the containers straight from the borrowed bytes without allocating them.
+/// This makes it a good fit for bitmaps stored in memory-mapped files.
+///
+/// Only the layout of the buffer is checked when creating the view. Like
+/// [`RoaringBitmap::deserialize_unchecked_from`], the content of the containers is trusted,
+/// answers on an invalid bitmap are unspecified but memory safe.
+///
+/// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
+///
+/// # Examples
+///
+/// ```rust
+/// use roaring::{RoaringBitmap, RoaringBitmapView};
+///
+/// let rb: RoaringBitmap = (1..4).chain(100_000..200_000).collect();
+/// let mut bytes = vec![];
+/// rb.serialize_into(&mut bytes).unwrap();
+///
+/// let view = RoaringBitmapView::new(&bytes).unwrap();
+/// assert!(view.contains(150_000));
+/// assert_eq!(view.len(), rb.len());
+/// assert!(view.iter().eq(rb.iter()));
+/// ```
+#[derive(Clone)]
+pub struct RoaringBitmapView<'a> {
+    bytes: &'a [u8],
+    layout: Layout<'a>,
+    len: u64,
+}
+
+#[derive(Clone)]
+enum Layout<'a> {
+    Portable {
+        descriptions: &'a [u8],
+        run_flags: &'a [u8],
+        offsets: Offsets<'a>,
+    },
+    /// The frozen format groups the containers by type,
+    /// we compute their offsets and cardinalities once when creating the view.
+    Frozen {
+        keys: &'a [u8],
+        counts: &'a [u8],
+        typecodes: &'a [u8],
+        offsets: Vec<u32>,
+        cardinalities: Vec<u32>,
+    },
+}
+
+/// Small bitmaps with run containers are serialized without offsets,
+/// we compute them once when creating the view.
+#[derive(Clone, Copy)]
+enum Offsets<'a> {
+    Serialized(&'a [u8]),
+    Computed([u32; NO_OFFSET_THRESHOLD]),
+}
+
+#[derive(Clone, Copy)]
+struct ContainerView<'a> {
+    key: u16,
+    len: u64,
+    store: StoreView<'a>,
+}
+
+/// The little-endian payload of a serialized container.
+#[derive(Clone, Copy)]
+enum StoreView<'a> {
+    Array(&'a [u8]),
+    Bitmap(&'a [u8]),
+    /// The runs without the number of runs prefix.
+    Run(&'a [u8]),
+}
+
+impl<'a> RoaringBitmapView<'a> {
+    /// Creates a view over a bitmap serialized in [the standard Roaring on-disk format][format].
+    ///
+    /// Returns a [`DeserializeError`] if the cookie is unknown, if the keys are not sorted
+    /// or if the bytes are too short to hold the described containers.
+    ///
+    /// [format]: https://github.com/RoaringBitmap/RoaringFormatSpec
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    /// assert_eq!(view.to_bitmap(), rb);
+    /// assert!(RoaringBitmapView::new(&bytes[..bytes.len() - 1]).is_err());
+    /// ```
+    pub fn new(bytes: &'a [u8]) -> Result<RoaringBitmapView<'a>, DeserializeError> {
+        // First read the cookie to determine which version of the format we are reading
+        let cookie = read_u32(bytes, 0)?;
+        let (size, has_offsets, run_flags) = if cookie == SERIAL_COOKIE_NO_RUNCONTAINER {
+            (read_u32(bytes, 4)? as usize, true, &bytes[..0])
+        } else if (cookie as u16) == SERIAL_COOKIE {
+            let size = ((cookie >> 16) + 1) as usize;
+            (size, size >= NO_OFFSET_THRESHOLD, read_bytes(bytes, 4, (size + 7) / 8)?)
+        } else {
+            return Err(DeserializeError::UnknownCookie(cookie));
+        };
+
+        if size > u16::MAX as usize + 1 {
+            return Err(DeserializeError::TooManyContainers(size));
+        }
+
+        let mut position = if run_flags.is_empty() { 8 } else { 4 + run_flags.len() };
+        let descriptions = read_bytes(bytes, position, size * DESCRIPTION_BYTES)?;
+        position += descriptions.len();
+
+        let offsets = if has_offsets {
+            Offsets::Serialized(read_bytes(bytes, position, size * OFFSET_BYTES)?)
+        } else {
+            let mut offsets = [0; NO_OFFSET_THRESHOLD];
+            for (i, offset) in offsets.iter_mut().enumerate().take(size) {
+                *offset = position as u32;
+                position += payload_size(bytes, descriptions, run_flags, i, position)?;
+            }
+            Offsets::Computed(offsets)
+        };
+
+        let layout = Layout::Portable { descriptions, run_flags, offsets };
+        let mut view = RoaringBitmapView { bytes, layout, len: 0 };
+        let mut previous_key = None;
+        for i in 0..size {
+            let key = view.key(i);
+            if previous_key.map_or(false, |previous| previous >= key) {
+                return Err(DeserializeError::UnsortedKeys { key });
+            }
+            previous_key = Some(key);
+
+            let offset = view.offset(i);
+            let end = offset + payload_size(bytes, descriptions, run_flags, i, offset)?;
+            if end > bytes.len() {
+                return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+            }
+            view.len += cardinality(descriptions, i);
+        }
+
+        Ok(view)
+    }
+
+    /// Creates a view over a bitmap serialized in the CRoaring frozen format,
+    /// see [`RoaringBitmap::serialize_frozen_into`].
+    ///
+    /// Unlike CRoaring, the bytes do not need to be aligned. Returns a [`DeserializeError`]
+    /// if the header is unknown or if the containers do not fill the bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..200_000).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_frozen_into(&mut bytes).unwrap();
+    ///
+    /// let view = RoaringBitmapView::frozen(&bytes).unwrap();
+    /// assert!(view.contains(150_000));
+    /// assert_eq!(view.to_bitmap(), rb);
+    /// ```
+    pub fn frozen(bytes: &'a [u8]) -> Result<RoaringBitmapView<'a>, DeserializeError> {
+        // The header is stored at the end of the buffer
+        let header_offset = bytes
+            .len()
+            .checked_sub(4)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        let header = read_u32_unchecked(bytes, header_offset);
+        if header & 0x7FFF != FROZEN_COOKIE {
+            return Err(DeserializeError::UnknownCookie(header));
+        }
+
+        let size = (header >> 15) as usize;
+        if size > u16::MAX as usize + 1 {
+            return Err(DeserializeError::TooManyContainers(size));
+        }
+
+        let zones_len = header_offset
+            .checked_sub(5 * size)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::UnexpectedEof))?;
+        let keys = &bytes[zones_len..zones_len + 2 * size];
+        let counts = &bytes[zones_len + 2 * size..zones_len + 4 * size];
+        let typecodes = &bytes[zones_len + 4 * size..header_offset];
+
+        let (mut bitset_zone, mut run_zone, mut array_zone) = (0, 0, 0);
+        for (i, &typecode) in typecodes.iter().enumerate() {
+            let count = read_u16_unchecked(counts, i * 2) as usize;
+            match typecode {
+                FROZEN_BITSET_TYPE => bitset_zone += BITMAP_BYTES,
+                FROZEN_ARRAY_TYPE => array_zone += (count + 1) * 2,
+                FROZEN_RUN_TYPE => run_zone += count * RUN_BYTES,
+                _ => {
+                    let key = read_u16_unchecked(keys, i * 2);
+                    return Err(DeserializeError::InvalidTypecode { key, typecode });
+                }
+            }
+        }
+        let expected = bitset_zone + run_zone + array_zone;
+        if expected != zones_len {
+            return Err(DeserializeError::SizeMismatch { expected, actual: zones_len });
+        }
+
+        // Containers of each type are stored in order, one zone after the other
+        let (mut bitset_offset, mut run_offset, mut array_offset) =
+            (0, bitset_zone, bitset_zone + run_zone);
+        let mut offsets = Vec::with_capacity(size);
+        let mut cardinalities = Vec::with_capacity(size);
+        for (i, &typecode) in typecodes.iter().enumerate() {
+            let count = read_u16_unchecked(counts, i * 2) as usize;
+            let offset = match typecode {
+                FROZEN_BITSET_TYPE => &mut bitset_offset,
+                FROZEN_ARRAY_TYPE => &mut array_offset,
+                _ => &mut run_offset,
+            };
+            offsets.push(*offset as u32);
+            cardinalities.push(match typecode {
+                FROZEN_BITSET_TYPE | FROZEN_ARRAY_TYPE => count as u32 + 1,
+                // The frozen format stores the number of runs instead of the cardinality
+                _ => {
+                    let runs = &bytes[*offset..*offset + count * RUN_BYTES];
+                    (0..count).map(|i| run(runs, i).len() as u32).sum()
+                }
+            });
+            *offset += match typecode {
+                FROZEN_BITSET_TYPE => BITMAP_BYTES,
+                FROZEN_ARRAY_TYPE => (count + 1) * 2,
+                _ => count * RUN_BYTES,
+            };
+        }
+
+        let layout = Layout::Frozen { keys, counts, typecodes, offsets, cardinalities };
+        let mut view = RoaringBitmapView { bytes, layout, len: 0 };
+        let mut previous_key = None;
+        for i in 0..size {
+            let key = view.key(i);
+            if previous_key.map_or(false, |previous| previous >= key) {
+                return Err(DeserializeError::UnsortedKeys { key });
+            }
+            previous_key = Some(key);
+        }
+        view.len = (0..size).map(|i| view.container_len(i)).sum();
+
+        Ok(view)
+    }
+
+    /// Returns the number of distinct integers in the viewed bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(RoaringBitmapView::new(&bytes).unwrap().len(), 3);
+    /// ```
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns `true` if there are no integers in the viewed bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let mut bytes = vec![];
+    /// RoaringBitmap::new().serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert!(RoaringBitmapView::new(&bytes).unwrap().is_empty());
+    /// ```
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the viewed bitmap contains the specified integer.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert!(view.contains(1));
+    /// assert!(!view.contains(4));
+    /// ```
+    pub fn contains(&self, value: u32) -> bool {
+        let (key, index) = util::split(value);
+        match self.find(key) {
+            Ok(i) => self.container(i).store.contains(index),
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the minimum value in the viewed bitmap (if it is not empty).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (3..4).chain(100_000..100_005).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(RoaringBitmapView::new(&bytes).unwrap().min(), Some(3));
+    /// ```
+    pub fn min(&self) -> Option<u32> {
+        self.containers().find_map(|c| c.store.min().map(|index| util::join(c.key, index)))
+    }
+
+    /// Returns the maximum value in the viewed bitmap (if it is not empty).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (3..4).chain(100_000..100_005).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(RoaringBitmapView::new(&bytes).unwrap().max(), Some(100_004));
+    /// ```
+    pub fn max(&self) -> Option<u32> {
+        (0..self.size())
+            .rev()
+            .map(|i| self.container(i))
+            .find_map(|c| c.store.max().map(|index| util::join(c.key, index)))
+    }
+
+    /// Returns the number of integers that are <= value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..100_005).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.rank(0), 0);
+    /// assert_eq!(view.rank(2), 2);
+    /// assert_eq!(view.rank(100_000), 4);
+    /// ```
+    pub fn rank(&self, value: u32) -> u64 {
+        let (key, index) = util::split(value);
+        let (before, container) = match self.find(key) {
+            Ok(i) => (i, Some(self.container(i))),
+            Err(i) => (i, None),
+        };
+
+        let below: u64 = (0..before).map(|i| self.container_len(i)).sum();
+        below + container.map_or(0, |c| c.store.rank(index))
+    }
+
+    /// Returns the `n`th integer in the viewed bitmap or `None` if `n >= len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..100_005).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.select(0), Some(1));
+    /// assert_eq!(view.select(3), Some(100_000));
+    /// assert_eq!(view.select(8), None);
+    /// ```
+    pub fn select(&self, n: u32) -> Option<u32> {
+        let mut n = n as u64;
+        for container in self.containers() {
+            if n < container.len {
+                return container
+                    .store
+                    .select(n as u16)
+                    .map(|index| util::join(container.key, index));
+            }
+            n -= container.len;
+        }
+        None
+    }
+
+    /// Iterator over each value stored in the viewed bitmap, guarantees values are ordered by value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.iter().collect::<Vec<u32>>(), vec![1, 2, 3]);
+    /// ```
+    pub fn iter(&self) -> ViewIter<'_> {
+        ViewIter { view: self, front: None, containers: 0..self.size(), back: None }
+    }
+
+    /// Iterator over the values of the viewed bitmap within a range, guarantees values are ordered by value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).chain(100_000..100_005).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.range(3..=100_001).collect::<Vec<u32>>(), vec![3, 100_000, 100_001]);
+    /// ```
+    pub fn range<R>(&self, range: R) -> ViewIter<'_>
+    where
+        R: RangeBounds<u32>,
+    {
+        let range = match util::convert_range_to_inclusive(range) {
+            Ok(range) => range,
+            Err(_) => return ViewIter { view: self, front: None, containers: 0..0, back: None },
+        };
+
+        let (start_key, start_index) = util::split(*range.start());
+        let (end_key, end_index) = util::split(*range.end());
+        let first = match self.find(start_key) {
+            Ok(i) | Err(i) => i,
+        };
+        let last = match self.find(end_key) {
+            Ok(i) => i + 1,
+            Err(i) => i,
+        };
+
+        // The containers at the bounds of the range are only partially iterated
+        let bounded = |i: usize| {
+            let key = self.key(i);
+            let lo = if key == start_key { start_index } else { 0 };
+            let hi = if key == end_key { end_index } else { u16::MAX };
+            (lo > 0 || hi < u16::MAX).then(|| (key, self.container(i).iter_range(lo, hi)))
+        };
+        let mut iter = ViewIter { view: self, front: None, containers: first..last, back: None };
+        if let Some(front) = iter.containers.clone().next().and_then(bounded) {
+            iter.front = Some(front);
+            iter.containers.start += 1;
+        }
+        if let Some(back) = iter.containers.clone().next_back().and_then(bounded) {
+            iter.back = Some(back);
+            iter.containers.end -= 1;
+        }
+        iter
+    }
+
+    /// Computes the len of the intersection with an owned bitmap without allocating containers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.intersection_len(&rb2), (rb1 & rb2).len());
+    /// ```
+    pub fn intersection_len(&self, other: &RoaringBitmap) -> u64 {
+        self.containers()
+            .filter_map(|c| other.find_container(c.key).map(|o| c.intersection_len(o)))
+            .sum()
+    }
+
+    /// Computes the len of the union with an owned bitmap without allocating containers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.union_len(&rb2), (rb1 | rb2).len());
+    /// ```
+    pub fn union_len(&self, other: &RoaringBitmap) -> u64 {
+        self.len + other.len() - self.intersection_len(other)
+    }
+
+    /// Computes the len of the difference with an owned bitmap without allocating containers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.difference_len(&rb2), (rb1 - rb2).len());
+    /// ```
+    pub fn difference_len(&self, other: &RoaringBitmap) -> u64 {
+        self.len - self.intersection_len(other)
+    }
+
+    /// Computes the len of the symmetric difference with an owned bitmap without allocating
+    /// containers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let rb2: RoaringBitmap = (3..5).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert_eq!(view.symmetric_difference_len(&rb2), (rb1 ^ rb2).len());
+    /// ```
+    pub fn symmetric_difference_len(&self, other: &RoaringBitmap) -> u64 {
+        self.len + other.len() - 2 * self.intersection_len(other)
+    }
+
+    /// Returns true if the viewed bitmap has no elements in common with `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert!(view.is_disjoint(&(4..6).collect()));
+    /// assert!(!view.is_disjoint(&(3..6).collect()));
+    /// ```
+    pub fn is_disjoint(&self, other: &RoaringBitmap) -> bool {
+        self.containers()
+            .filter_map(|c| other.find_container(c.key).map(|o| (c, o)))
+            .all(|(c, o)| c.intersection_len(o) == 0)
+    }
+
+    /// Returns `true` if the viewed bitmap is a subset of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert!(view.is_subset(&(0..6).collect()));
+    /// assert!(!view.is_subset(&(2..6).collect()));
+    /// ```
+    pub fn is_subset(&self, other: &RoaringBitmap) -> bool {
+        self.containers().all(|c| {
+            other
+                .find_container(c.key)
+                .map_or(false, |o| c.len <= o.len() && c.intersection_len(o) == c.len)
+        })
+    }
+
+    /// Returns `true` if the viewed bitmap is a superset of `other`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb1: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb1.serialize_into(&mut bytes).unwrap();
+    /// let view = RoaringBitmapView::new(&bytes).unwrap();
+    ///
+    /// assert!(view.is_superset(&(2..4).collect()));
+    /// assert!(!view.is_superset(&(2..6).collect()));
+    /// ```
+    pub fn is_superset(&self, other: &RoaringBitmap) -> bool {
+        other.containers.iter().all(|o| match self.find(o.key) {
+            Ok(i) => {
+                let c = self.container(i);
+                o.len() <= c.len && c.intersection_len(o) == o.len()
+            }
+            Err(_) => false,
+        })
+    }
+
+    /// Copies the viewed bitmap into an owned [`RoaringBitmap`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::{RoaringBitmap, RoaringBitmapView};
+    ///
+    /// let rb: RoaringBitmap = (1..4).collect();
+    /// let mut bytes = vec![];
+    /// rb.serialize_into(&mut bytes).unwrap();
+    ///
+    /// assert_eq!(RoaringBitmapView::new(&bytes).unwrap().to_bitmap(), rb);
+    /// ```
+    pub fn to_bitmap(&self) -> RoaringBitmap {
+        let containers = self.containers().map(|c| c.to_container()).collect();
+        RoaringBitmap { containers }
+    }
+
+    fn size(&self) -> usize {
+        match &self.layout {
+            Layout::Portable { descriptions, .. } => descriptions.len() / DESCRIPTION_BYTES,
+            Layout::Frozen { keys, .. } => keys.len() / 2,
+        }
+    }
+
+    fn key(&self, i: usize) -> u16 {
+        match &self.layout {
+            Layout::Portable { descriptions, .. } => {
+                read_u16_unchecked(descriptions, i * DESCRIPTION_BYTES)
+            }
+            Layout::Frozen { keys, .. } => read_u16_unchecked(keys, i * 2),
+        }
+    }
+
+    fn offset(&self, i: usize) -> usize {
+        match &self.layout {
+            Layout::Portable { offsets: Offsets::Serialized(offsets), .. } => {
+                read_u32_unchecked(offsets, i * OFFSET_BYTES) as usize
+            }
+            Layout::Portable { offsets: Offsets::Computed(offsets), .. } => offsets[i] as usize,
+            Layout::Frozen { offsets, .. } => offsets[i] as usize,
+        }
+    }
+
+    fn find(&self, key: u16) -> Result<usize, usize> {
+        let (mut low, mut high) = (0, self.size());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.key(mid).cmp(&key) {
+                Ordering::Less => low = mid + 1,
+                Ordering::Greater => high = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(low)
+    }
+
+    fn container_len(&self, i: usize) -> u64 {
+        match &self.layout {
+            Layout::Portable { descriptions, .. } => cardinality(descriptions, i),
+            Layout::Frozen { cardinalities, .. } => u64::from(cardinalities[i]),
+        }
+    }
+
+    fn container(&self, i: usize) -> ContainerView<'a> {
+        let key = self.key(i);
+        let offset = self.offset(i);
+        // The bounds of every container have been checked when creating the view
+        match &self.layout {
+            Layout::Portable { descriptions, run_flags, .. } => {
+                let len = cardinality(descriptions, i);
+                let store = if is_run(run_flags, i) {
+                    let runs = read_u16_unchecked(self.bytes, offset) as usize;
+                    StoreView::Run(&self.bytes[offset + 2..offset + 2 + runs * RUN_BYTES])
+                } else if len <= ARRAY_LIMIT {
+                    StoreView::Array(&self.bytes[offset..offset + len as usize * 2])
+                } else {
+                    StoreView::Bitmap(&self.bytes[offset..offset + BITMAP_BYTES])
+                };
+                ContainerView { key, len, store }
+            }
+            Layout::Frozen { counts, typecodes, .. } => {
+                let count = read_u16_unchecked(counts, i * 2) as usize;
+                let store = match typecodes[i] {
+                    FROZEN_BITSET_TYPE => {
+                        StoreView::Bitmap(&self.bytes[offset..offset + BITMAP_BYTES])
+                    }
+                    FROZEN_ARRAY_TYPE => {
+                        StoreView::Array(&self.bytes[offset..offset + (count + 1) * 2])
+                    }
+                    _ => StoreView::Run(&self.bytes[offset..offset + count * RUN_BYTES]),
+                };
+                ContainerView { key, len: self.container_len(i), store }
+            }
+        }
+    }
+
+    fn containers(&self) -> impl Iterator<Item = ContainerView<'a>> + '_ {
+        (0..self.size()).map(move |i| self.container(i))
+    }
+}
+
+impl<'a> ContainerView<'a> {
+    fn iter(&self) -> StoreViewIter<'a> {
+        self.store.iter_between(0, u16::MAX, self.len)
+    }
+
+    /// Iterates over the values between `lo` and `hi`, both included.
+    fn iter_range(&self, lo: u16, hi: u16) -> StoreViewIter<'a> {
+        let below = lo.checked_sub(1).map_or(0, |lo| self.store.rank(lo));
+        self.store.iter_between(lo, hi, self.store.rank(hi) - below)
+    }
+
+    fn intersection_len(&self, other: &Container) -> u64 {
+        match (self.store, &other.store) {
+            (StoreView::Bitmap(words), Store::Bitmap(bits)) => (0..BITMAP_LENGTH)
+                .map(|i| {
+                    (read_u64_unchecked(words, i * 8) & bits.as_array()[i]).count_ones() as u64
+                })
+                .sum(),
+            (StoreView::Run(_), Store::Bitmap(bits)) => {
+                self.store.runs().map(|iv| bits.range_cardinality(iv.range())).sum()
+            }
+            _ if self.len <= other.len() => {
+                self.iter().filter(|&index| other.contains(index)).count() as u64
+            }
+            _ => {
+                other.into_iter().filter(|&value| self.store.contains(value as u16)).count() as u64
+            }
+        }
+    }
+
+    fn to_container(self) -> Container {
+        let store = match self.store {
+            StoreView::Array(values) => {
+                let values = values.chunks_exact(2).map(|b| u16::from_le_bytes([b[0], b[1]]));
+                Store::Array(ArrayStore::from_vec_unchecked(values.collect()))
+            }
+            StoreView::Bitmap(words) => {
+                let mut bits = Box::new([0; BITMAP_LENGTH]);
+                for (i, word) in bits.iter_mut().enumerate() {
+                    *word = read_u64_unchecked(words, i * 8);
+                }
+                let len = bits.iter().map(|word| word.count_ones() as u64).sum();
+                Store::Bitmap(BitmapStore::from_unchecked(len, bits))
+            }
+            StoreView::Run(_) => {
+                let mut runs = IntervalStore::new();
+                self.store.runs().for_each(|iv| {
+                    runs.insert_range(iv.range());
+                });
+                Store::Run(runs)
+            }
+        };
+        let mut container = Container { key: self.key, store };
+        container.ensure_correct_store();
+        container
+    }
+
+    /// Adds the values of the view to the container.
+    fn union_into(&self, container: &mut Container) {
+        match self.store {
+            StoreView::Bitmap(words) => {
+                let bits = bitmap_store_mut(container);
+                bits.update_words(|key, word| word | read_u64_unchecked(words, key * 8));
+            }
+            StoreView::Array(_) => self.iter().for_each(|index| {
+                container.insert(index);
+            }),
+            StoreView::Run(_) => self.store.runs().for_each(|iv| {
+                container.insert_range(iv.range());
+            }),
+        }
+    }
+
+    /// Removes the values of the container that are not in the view.
+    fn intersect_into(&self, container: &mut Container) {
+        match (self.store, &mut container.store) {
+            (StoreView::Bitmap(words), Store::Bitmap(bits)) => {
+                bits.update_words(|key, word| word & read_u64_unchecked(words, key * 8));
+                container.ensure_correct_store();
+            }
+            _ => container.retain(|index| self.store.contains(index)),
+        }
+    }
+
+    /// Removes the values of the view from the container.
+    fn difference_into(&self, container: &mut Container) {
+        match (self.store, &mut container.store) {
+            (StoreView::Bitmap(words), Store::Bitmap(bits)) => {
+                bits.update_words(|key, word| word & !read_u64_unchecked(words, key * 8));
+                container.ensure_correct_store();
+            }
+            (StoreView::Bitmap(_), _) => container.retain(|index| !self.store.contains(index)),
+            (StoreView::Array(_), _) => self.iter().for_each(|index| {
+                container.remove(index);
+            }),
+            (StoreView::Run(_), _) => self.store.runs().for_each(|iv| {
+                container.remove_range(iv.range());
+            }),
+        }
+    }
+
+    /// Toggles the values of the view in the container.
+    fn symmetric_difference_into(&self, container: &mut Container) {
+        match self.store {
+            StoreView::Bitmap(words) => {
+                let bits = bitmap_store_mut(container);
+                bits.update_words(|key, word| word ^ read_u64_unchecked(words, key * 8));
+                container.ensure_correct_store();
+            }
+            StoreView::Array(_) => self.iter().for_each(|index| {
+                if !container.remove(index) {
+                    container.insert(index);
+                }
+            }),
+            StoreView::Run(_) => self.store.runs().for_each(|iv| container.flip_range(iv.range())),
+        }
+    }
+}
+
+/// Converts the store of the container to a bitmap, to combine it word by word with a view.
+fn bitmap_store_mut(container: &mut Container) -> &mut BitmapStore {
+    if !matches!(container.store, Store::Bitmap(_)) {
+        container.store = container.store.to_bitmap();
+    }
+    match &mut container.store {
+        Store::Bitmap(bits) => bits,
+        _ => unreachable!(),
+    }
+}
+
+impl<'a> StoreView<'a> {
+    fn contains(&self, index: u16) -> bool {
+        match *self {
+            StoreView::Array(values) => {
+                let position =
+                    partition_point(values.len() / 2, |i| array_value(values, i) < index);
+                position < values.len() / 2 && array_value(values, position) == index
+            }
+            StoreView::Bitmap(words) => {
+                let word = read_u64_unchecked(words, index as usize / 64 * 8);
+                word & (1 << (index % 64)) != 0
+            }
+            StoreView::Run(runs) => {
+                let position =
+                    partition_point(runs.len() / RUN_BYTES, |i| run(runs, i).end() < index);
+                position < runs.len() / RUN_BYTES && run(runs, position).start() <= index
+            }
+        }
+    }
+
+    fn rank(&self, index: u16) -> u64 {
+        match *self {
+            StoreView::Array(values) => {
+                partition_point(values.len() / 2, |i| array_value(values, i) <= index) as u64
+            }
+            StoreView::Bitmap(words) => {
+                let key = index as usize / 64;
+                let below: u64 =
+                    (0..key).map(|i| read_u64_unchecked(words, i * 8).count_ones() as u64).sum();
+                let mask = u64::MAX >> (63 - index % 64);
+                below + (read_u64_unchecked(words, key * 8) & mask).count_ones() as u64
+            }
+            StoreView::Run(_) => self
+                .runs()
+                .take_while(|iv| iv.start() <= index)
+                .map(|iv| Interval::new(iv.start(), iv.end().min(index)).len())
+                .sum(),
+        }
+    }
+
+    fn select(&self, n: u16) -> Option<u16> {
+        match *self {
+            StoreView::Array(values) => {
+                ((n as usize) < values.len() / 2).then(|| array_value(values, n as usize))
+            }
+            StoreView::Bitmap(words) => {
+                let mut n = n as u32;
+                for key in 0..BITMAP_LENGTH {
+                    let mut word = read_u64_unchecked(words, key * 8);
+                    let len = word.count_ones();
+                    if n < len {
+                        for _ in 0..n {
+                            word &= word - 1;
+                        }
+                        return Some((key * 64) as u16 + word.trailing_zeros() as u16);
+                    }
+                    n -= len;
+                }
+                None
+            }
+            StoreView::Run(_) => {
+                let mut n = n as u64;
+                for iv in self.runs() {
+                    if n < iv.len() {
+                        return Some(iv.start() + n as u16);
+                    }
+                    n -= iv.len();
+                }
+                None
+            }
+        }
+    }
+
+    fn min(&self) -> Option<u16> {
+        match *self {
+            StoreView::Array(values) => (!values.is_empty()).then(|| array_value(values, 0)),
+            StoreView::Bitmap(words) => (0..BITMAP_LENGTH).find_map(|key| {
+                let word = read_u64_unchecked(words, key * 8);
+                (word != 0).then(|| (key * 64 + word.trailing_zeros() as usize) as u16)
+            }),
+            StoreView::Run(runs) => (!runs.is_empty()).then(|| run(runs, 0).start()),
+        }
+    }
+
+    fn max(&self) -> Option<u16> {
+        match *self {
+            StoreView::Array(values) => {
+                values.len().checked_sub(2).map(|_| array_value(values, values.len() / 2 - 1))
+            }
+            StoreView::Bitmap(words) => (0..BITMAP_LENGTH).rev().find_map(|key| {
+                let word = read_u64_unchecked(words, key * 8);
+                (word != 0).then(|| (key * 64 + 63 - word.leading_zeros() as usize) as u16)
+            }),
+            StoreView::Run(runs) => {
+                (runs.len() / RUN_BYTES).checked_sub(1).map(|last| run(runs, last).end())
+            }
+        }
+    }
+
+    fn runs(&self) -> impl Iterator<Item = Interval> + 'a {
+        let runs = match *self {
+            StoreView::Run(runs) => runs,
+            StoreView::Array(_) | StoreView::Bitmap(_) => &[],
+        };
+        (0..runs.len() / RUN_BYTES).map(move |i| run(runs, i))
+    }
+
+    /// Iterates over the `len` values between `lo` and `hi`, both included.
+    fn iter_between(&self, lo: u16, hi: u16, len: u64) -> StoreViewIter<'a> {
+        match *self {
+            StoreView::Array(values) => {
+                let front = partition_point(values.len() / 2, |i| array_value(values, i) < lo);
+                let back = partition_point(values.len() / 2, |i| array_value(values, i) <= hi);
+                StoreViewIter::Array { values, front, back }
+            }
+            StoreView::Bitmap(words) => {
+                let (lo_key, hi_key) = (lo as usize / 64, hi as usize / 64);
+                let front = read_u64_unchecked(words, lo_key * 8) & (u64::MAX << (lo % 64));
+                let back = read_u64_unchecked(words, hi_key * 8) & (u64::MAX >> (63 - hi % 64));
+                StoreViewIter::Bitmap { words, front: (lo_key, front), back: (hi_key, back), len }
+            }
+            StoreView::Run(runs) => {
+                let n_runs = runs.len() / RUN_BYTES;
+                let front = partition_point(n_runs, |i| run(runs, i).end() < lo);
+                let back = partition_point(n_runs, |i| run(runs, i).start() <= hi);
+                StoreViewIter::Run {
+                    runs,
+                    front: (front, lo),
+                    back: (back.wrapping_sub(1), hi),
+                    len,
+                }
+            }
+        }
+    }
+}
+
+/// An iterator over the values of a store view, from both ends.
+enum StoreViewIter<'a> {
+    /// The values between the `front` and `back` indexes, `back` excluded.
+    Array { values: &'a [u8], front: usize, back: usize },
+    /// The key and the bits left of the front and back words, `len` values are left.
+    Bitmap { words: &'a [u8], front: (usize, u64), back: (usize, u64), len: u64 },
+    /// The index of the front and back runs and their next values, `len` values are left.
+    Run { runs: &'a [u8], front: (usize, u16), back: (usize, u16), len: u64 },
+}
+
+impl StoreViewIter<'_> {
+    fn len(&self) -> u64 {
+        match *self {
+            StoreViewIter::Array { front, back, .. } => (back - front) as u64,
+            StoreViewIter::Bitmap { len, .. } | StoreViewIter::Run { len, .. } => len,
+        }
+    }
+}
+
+impl Iterator for StoreViewIter<'_> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        match self {
+            StoreViewIter::Array { values, front, back } => {
+                if *front < *back {
+                    *front += 1;
+                    Some(array_value(values, *front - 1))
+                } else {
+                    None
+                }
+            }
+            StoreViewIter::Bitmap { words, front: (key, word), len, .. } => {
+                if *len == 0 {
+                    return None;
+                }
+                while *word == 0 {
+                    *key += 1;
+                    *word = read_u64_unchecked(words, *key * 8);
+                }
+                *len -= 1;
+                let index = *key * 64 + word.trailing_zeros() as usize;
+                *word &= *word - 1;
+                Some(index as u16)
+            }
+            StoreViewIter::Run { runs, front: (index, next), len, .. } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                let iv = run(runs, *index);
+                let value = (*next).max(iv.start());
+                if value == iv.end() {
+                    *index += 1;
+                    *next = 0;
+                } else {
+                    *next = value + 1;
+                }
+                Some(value)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len() as usize;
+        (len, Some(len))
+    }
+}
+
+impl DoubleEndedIterator for StoreViewIter<'_> {
+    fn next_back(&mut self) -> Option<u16> {
+        match self {
+            StoreViewIter::Array { values, front, back } => {
+                if *front < *back {
+                    *back -= 1;
+                    Some(array_value(values, *back))
+                } else {
+                    None
+                }
+            }
+            StoreViewIter::Bitmap { words, back: (key, word), len, .. } => {
+                if *len == 0 {
+                    return None;
+                }
+                while *word == 0 {
+                    *key -= 1;
+                    *word = read_u64_unchecked(words, *key * 8);
+                }
+                *len -= 1;
+                let bit = 63 - word.leading_zeros() as usize;
+                *word &= !(1 << bit);
+                Some((*key * 64 + bit) as u16)
+            }
+            StoreViewIter::Run { runs, back: (index, previous), len, .. } => {
+                if *len == 0 {
+                    return None;
+                }
+                *len -= 1;
+                let iv = run(runs, *index);
+                let value = (*previous).min(iv.end());
+                if value == iv.start() {
+                    *index = index.wrapping_sub(1);
+                    *previous = u16::MAX;
+                } else {
+                    *previous = value - 1;
+                }
+                Some(value)
+            }
+        }
+    }
+}
+
+/// An iterator for `RoaringBitmapView`.
+pub struct ViewIter<'a> {
+    view: &'a RoaringBitmapView<'a>,
+    front: Option<(u16, StoreViewIter<'a>)>,
+    /// The indexes of the containers that are not yet iterated from either end.
+    containers: Range<usize>,
+    back: Option<(u16, StoreViewIter<'a>)>,
+}
+
+impl Iterator for ViewIter<'_> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        loop {
+            if let Some((key, inner)) = &mut self.front {
+                if let Some(index) = inner.next() {
+                    return Some(util::join(*key, index));
+                }
+                self.front = None;
+            }
+            match self.containers.next() {
+                Some(i) => {
+                    let container = self.view.container(i);
+                    self.front = Some((container.key, container.iter()));
+                }
+                None => {
+                    let (key, inner) = self.back.as_mut()?;
+                    return inner.next().map(|index| util::join(*key, index));
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let partial =
+            |iter: &Option<(u16, StoreViewIter)>| iter.as_ref().map_or(0, |(_, i)| i.len());
+        let full: u64 = self.containers.clone().map(|i| self.view.container_len(i)).sum();
+        match usize::try_from(partial(&self.front) + full + partial(&self.back)) {
+            Ok(size) => (size, Some(size)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl DoubleEndedIterator for ViewIter<'_> {
+    fn next_back(&mut self) -> Option<u32> {
+        loop {
+            if let Some((key, inner)) = &mut self.back {
+                if let Some(index) = inner.next_back() {
+                    return Some(util::join(*key, index));
+                }
+                self.back = None;
+            }
+            match self.containers.next_back() {
+                Some(i) => {
+                    let container = self.view.container(i);
+                    self.back = Some((container.key, container.iter()));
+                }
+                None => {
+                    let (key, inner) = self.front.as_mut()?;
+                    return inner.next_back().map(|index| util::join(*key, index));
+                }
+            }
+        }
+    }
+}
+
+impl ExactSizeIterator for ViewIter<'_> {}
+impl FusedIterator for ViewIter<'_> {}
+
+impl<'a> IntoIterator for &'a RoaringBitmapView<'a> {
+    type Item = u32;
+    type IntoIter = ViewIter<'a>;
+
+    fn into_iter(self) -> ViewIter<'a> {
+        self.iter()
+    }
+}
+
+impl From<RoaringBitmapView<'_>> for RoaringBitmap {
+    fn from(view: RoaringBitmapView<'_>) -> RoaringBitmap {
+        view.to_bitmap()
+    }
+}
+
+impl BitOrAssign<&RoaringBitmapView<'_>> for RoaringBitmap {
+    /// An `union` between an owned bitmap and a view, the containers of the view
+    /// are only copied for the keys missing from the owned bitmap.
+    fn bitor_assign(&mut self, rhs: &RoaringBitmapView<'_>) {
+        for container in rhs.containers() {
+            match self.containers.binary_search_by_key(&container.key, |c| c.key) {
+                Err(loc) => self.containers.insert(loc, container.to_container()),
+                Ok(loc) => container.union_into(&mut self.containers[loc]),
+            }
+        }
+    }
+}
+
+impl BitAndAssign<&RoaringBitmapView<'_>> for RoaringBitmap {
+    /// An `intersection` between an owned bitmap and a view, the containers of the view
+    /// are never copied.
+    fn bitand_assign(&mut self, rhs: &RoaringBitmapView<'_>) {
+        self.containers.retain_mut(|cont| match rhs.find(cont.key) {
+            Ok(i) => {
+                rhs.container(i).intersect_into(cont);
+                !cont.is_empty()
+            }
+            Err(_) => false,
+        })
+    }
+}
+
+impl SubAssign<&RoaringBitmapView<'_>> for RoaringBitmap {
+    /// A `difference` between an owned bitmap and a view, the containers of the view
+    /// are never copied.
+    fn sub_assign(&mut self, rhs: &RoaringBitmapView<'_>) {
+        self.containers.retain_mut(|cont| match rhs.find(cont.key) {
+            Ok(i) => {
+                rhs.container(i).difference_into(cont);
+                !cont.is_empty()
+            }
+            Err(_) => true,
+        })
+    }
+}
+
+impl BitXorAssign<&RoaringBitmapView<'_>> for RoaringBitmap {
+    /// A `symmetric difference` between an owned bitmap and a view, the containers
+    /// of the view are only copied for the keys missing from the owned bitmap.
+    fn bitxor_assign(&mut self, rhs: &RoaringBitmapView<'_>) {
+        for container in rhs.containers() {
+            match self.containers.binary_search_by_key(&container.key, |c| c.key) {
+                Err(loc) => self.containers.insert(loc, container.to_container()),
+                Ok(loc) => {
+                    container.symmetric_difference_into(&mut self.containers[loc]);
+                    if self.containers[loc].is_empty() {
+                        self.containers.remove(loc);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn cardinality(descriptions: &[u8], i: usize) -> u64 {
+    u64::from(read_u16_unchecked(descriptions, i * DESCRIPTION_BYTES + 2)) + 1
+}
+
+pub(super) fn is_run(run_flags: &[u8], i: usize) -> bool {
+    run_flags.get(i / 8).map_or(false, |flags| flags & (1 << (i % 8)) != 0)
+}
+
+/// The number of bytes taken by the payload of the `i`th container.
+fn payload_size(
+    bytes: &[u8],
+    descriptions: &[u8],
+    run_flags: &[u8],
+    i: usize,
+    offset: usize,
+) -> io::Result<usize> {
+    if is_run(run_flags, i) {
+        Ok(2 + read_u16(bytes, offset)? as usize * RUN_BYTES)
+    } else if cardinality(descriptions, i) <= ARRAY_LIMIT {
+        Ok(cardinality(descriptions, i) as usize * 2)
+    } else {
+        Ok(BITMAP_BYTES)
+    }
+}
+
+fn partition_point(len: usize, pred: impl Fn(usize) -> bool) -> usize {
+    let (mut low, mut high) = (0, len);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if pred(mid) {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+pub(super) fn array_value(values: &[u8], i: usize) -> u16 {
+    read_u16_unchecked(values, i * 2)
+}
+
+fn run(runs: &[u8], i: usize) -> Interval {
+    let start = read_u16_unchecked(runs, i * RUN_BYTES);
+    let len = read_u16_unchecked(runs, i * RUN_BYTES + 2);
+    Interval::new(start, start.saturating_add(len))
+}
+
+pub(super) fn read_bytes(bytes: &[u8], offset: usize, len: usize) -> io::Result<&[u8]> {
+    offset
+        .checked_add(len)
+        .and_then(|end| bytes.get(offset..end))
+        .ok_or_else(|| io::ErrorKind::UnexpectedEof.into())
+}
+
+fn read_u16(bytes: &[u8], offset: usize) -> io::Result<u16> {
+    read_bytes(bytes, offset, 2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> io::Result<u32> {
+    read_bytes(bytes, offset, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+pub(super) fn read_u16_unchecked(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+pub(super) fn read_u32_unchecked(bytes: &[u8], offset: usize) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(buf)
+}
+
+pub(super) fn read_u64_unchecked(bytes: &[u8], offset: usize) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(&bytes[offset..offset + 8]);
+    u64::from_le_bytes(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeserializeError, RoaringBitmapView, BITMAP_BYTES, FROZEN_BITSET_TYPE};
+    use crate::RoaringBitmap;
+    use proptest::prelude::*;
+
+    fn serialize(bitmap: &RoaringBitmap) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bitmap.serialize_into(&mut bytes).unwrap();
+        bytes
+    }
+
+    proptest! {
+        #[test]
+        fn view_matches_bitmap(
+            mut bitmap in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+            values in prop::collection::vec(any::<u32>(), 16),
+        ) {
+            if optimize {
+                bitmap.optimize();
+            }
+            let bytes = serialize(&bitmap);
+            let view = RoaringBitmapView::new(&bytes).unwrap();
+
+            prop_assert_eq!(view.len(), bitmap.len());
+            prop_assert_eq!(view.min(), bitmap.min());
+            prop_assert_eq!(view.max(), bitmap.max());
+            prop_assert!(view.iter().eq(bitmap.iter()));
+            prop_assert!(view.iter().rev().eq(bitmap.iter().rev()));
+            prop_assert_eq!(view.iter().len(), bitmap.iter().len());
+            prop_assert_eq!(view.to_bitmap(), bitmap.clone());
+            for value in values {
+                let value = value % 0x10_0000;
+                prop_assert_eq!(view.contains(value), bitmap.contains(value));
+                prop_assert_eq!(view.rank(value), bitmap.rank(value));
+                prop_assert_eq!(view.select(value % 0x1000), bitmap.select(value % 0x1000));
+                prop_assert!(view.range(value..).eq(bitmap.range(value..)));
+                prop_assert!(view.range(..=value).eq(bitmap.range(..=value)));
+                let range = value..value + 0x1_8000;
+                prop_assert!(view.range(range.clone()).rev().eq(bitmap.range(range.clone()).rev()));
+                prop_assert_eq!(view.range(range.clone()).len(), bitmap.range(range).len());
+            }
+        }
+
+        #[test]
+        fn view_ops_match_bitmap_ops(
+            a in RoaringBitmap::arbitrary(),
+            mut b in RoaringBitmap::arbitrary(),
+        ) {
+            b.optimize();
+            let bytes = serialize(&b);
+            let view = RoaringBitmapView::new(&bytes).unwrap();
+
+            prop_assert_eq!(view.intersection_len(&a), (&a & &b).len());
+            prop_assert_eq!(view.union_len(&a), (&a | &b).len());
+            prop_assert_eq!(view.difference_len(&a), (&b - &a).len());
+            prop_assert_eq!(view.symmetric_difference_len(&a), (&a ^ &b).len());
+            prop_assert_eq!(view.is_disjoint(&a), b.is_disjoint(&a));
+            prop_assert_eq!(view.is_subset(&a), b.is_subset(&a));
+            prop_assert_eq!(view.is_superset(&a), b.is_superset(&a));
+
+            let mut or = a.clone();
+            or |= &view;
+            prop_assert_eq!(or, &a | &b);
+            let mut and = a.clone();
+            and &= &view;
+            prop_assert_eq!(and, &a & &b);
+            let mut sub = a.clone();
+            sub -= &view;
+            prop_assert_eq!(sub, &a - &b);
+            let mut xor = a.clone();
+            xor ^= &view;
+            prop_assert_eq!(xor, &a ^ &b);
+        }
+
+        #[test]
+        fn frozen_view_matches_bitmap(
+            mut bitmap in RoaringBitmap::arbitrary(),
+            optimize in any::<bool>(),
+            values in prop::collection::vec(any::<u32>(), 16),
+        ) {
+            if optimize {
+                bitmap.optimize();
+            }
+            let mut bytes = Vec::new();
+            bitmap.serialize_frozen_into(&mut bytes).unwrap();
+            prop_assert_eq!(bytes.len(), bitmap.frozen_size());
+            let view = RoaringBitmapView::frozen(&bytes).unwrap();
+
+            prop_assert_eq!(view.len(), bitmap.len());
+            prop_assert!(view.iter().eq(bitmap.iter()));
+            prop_assert!(view.iter().rev().eq(bitmap.iter().rev()));
+            prop_assert_eq!(view.to_bitmap(), bitmap.clone());
+            for value in values {
+                let value = value % 0x10_0000;
+                prop_assert_eq!(view.contains(value), bitmap.contains(value));
+                prop_assert_eq!(view.rank(value), bitmap.rank(value));
+                prop_assert!(view.range(value..).rev().eq(bitmap.range(value..).rev()));
+            }
+        }
+    }
+
+    #[test]
+    fn view_iter_from_both_ends() {
+        let mut bitmap: RoaringBitmap =
+            (0..3).chain(70_000..70_100).chain(200_000..205_000).collect();
+        for optimize in [false, true] {
+            if optimize {
+                bitmap.optimize();
+            }
+            let bytes = serialize(&bitmap);
+            let view = RoaringBitmapView::new(&bytes).unwrap();
+
+            let mut iter = view.range(1..204_999);
+            let mut expected = bitmap.range(1..204_999);
+            while iter.len() > 0 {
+                assert_eq!(iter.len(), expected.len());
+                assert_eq!(iter.next(), expected.next());
+                assert_eq!(iter.next_back(), expected.next_back());
+            }
+            assert_eq!((iter.next(), iter.next_back()), (None, None));
+        }
+    }
+
+    #[test]
+    fn view_rejects_truncated_bytes() {
+        let mut bitmap: RoaringBitmap = (0..10).chain(100_000..200_000).collect();
+        for optimize in [false, true] {
+            if optimize {
+                bitmap.optimize();
+            }
+            let bytes = serialize(&bitmap);
+            for len in 0..bytes.len() {
+                assert!(RoaringBitmapView::new(&bytes[..len]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn frozen_view_rejects_truncated_bytes() {
+        let mut bitmap: RoaringBitmap = (0..10).chain(100_000..200_000).collect();
+        for optimize in [false, true] {
+            if optimize {
+                bitmap.optimize();
+            }
+            let mut bytes = Vec::new();
+            bitmap.serialize_frozen_into(&mut bytes).unwrap();
+            for start in 1..bytes.len() {
+                assert!(RoaringBitmapView::frozen(&bytes[start..]).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn frozen_view_rejects_corrupted_bytes() {
+        let error = |bytes: &[u8]| RoaringBitmapView::frozen(bytes).err().unwrap();
+
+        let bitmap: RoaringBitmap = (1..4).collect();
+        let mut bytes = Vec::new();
+        bitmap.serialize_frozen_into(&mut bytes).unwrap();
+        let typecode = bytes.len() - 5;
+
+        let mut header = bytes.clone();
+        header[typecode + 1] ^= 1;
+        let cookie = u32::from_le_bytes(header[typecode + 1..].try_into().unwrap());
+        assert!(matches!(error(&header), DeserializeError::UnknownCookie(c) if c == cookie));
+
+        let mut unknown = bytes.clone();
+        unknown[typecode] = 9;
+        assert!(matches!(
+            error(&unknown),
+            DeserializeError::InvalidTypecode { key: 0, typecode: 9 }
+        ));
+
+        bytes[typecode] = FROZEN_BITSET_TYPE;
+        assert!(matches!(
+            error(&bytes),
+            DeserializeError::SizeMismatch { expected: BITMAP_BYTES, actual: 6 }
+        ));
+    }
+}
@@ -29,6 +29,8 @@ pub mod bitmap;
 pub mod treemap;
 
 pub use bitmap::RoaringBitmap;
+#[cfg(feature = "std")]
+pub use bitmap::RoaringBitmapView;
 pub use treemap::RoaringTreemap;
 
 /// An error type that is returned when an iterator isn't sorted.
@@ -53,6 +55,39 @@ impl fmt::Display for NonSortedIntegers {
 #[cfg(feature = "std")]
 impl std::error::Error for NonSortedIntegers {}
 
+/// An error type that is returned when the values of an appended set
+/// are not all greater than the values of the set it is appended to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OverlappingValues {
+    max: u64,
+    appended_min: u64,
+}
+
+impl OverlappingValues {
+    /// Returns the greatest value of the set that was appended to.
+    pub fn max(&self) -> u64 {
+        self.max
+    }
+
+    /// Returns the smallest value of the appended set, which is not greater than [`max`](Self::max).
+    pub fn appended_min(&self) -> u64 {
+        self.appended_min
+    }
+}
+
+impl fmt::Display for OverlappingValues {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "the appended values start at {} which is not greater than {}",
+            self.appended_min, self.max
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OverlappingValues {}
+
 /// A [`Iterator::collect`] blanket implementation that provides extra methods for [`RoaringBitmap`]
 /// and [`RoaringTreemap`].
 ///
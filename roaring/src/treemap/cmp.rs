@@ -1,6 +1,9 @@
 use alloc::collections::btree_map;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
 use core::iter::Peekable;
 
+use crate::bitmap::cmp_ranges;
 use crate::RoaringBitmap;
 use crate::RoaringTreemap;
 
@@ -106,6 +109,30 @@ impl RoaringTreemap {
     }
 }
 
+impl Eq for RoaringTreemap {}
+
+// `Ord` is not implemented as its `min` and `max` methods would shadow
+// the inherent ones when called on an owned treemap.
+impl PartialOrd for RoaringTreemap {
+    /// Compares the sorted values of the sets lexicographically,
+    /// run by run rather than value by value.
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(cmp_ranges(self.ranges(), other.ranges()))
+    }
+}
+
+impl Hash for RoaringTreemap {
+    /// Hashes the values of the set, equal sets have the same hash
+    /// whatever the stores they are using.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.map.len());
+        for (hi, bitmap) in &self.map {
+            hi.hash(state);
+            bitmap.hash(state);
+        }
+    }
+}
+
 impl<'a> Iterator for Pairs<'a> {
     type Item = (Option<&'a RoaringBitmap>, Option<&'a RoaringBitmap>);
 
@@ -1,9 +1,9 @@
 use alloc::collections::btree_map::{BTreeMap, Entry};
 use core::iter;
+use core::mem;
 use core::ops::RangeBounds;
 
-use crate::RoaringBitmap;
-use crate::RoaringTreemap;
+use crate::{OverlappingValues, RoaringBitmap, RoaringTreemap};
 
 use super::util;
 
@@ -227,6 +227,237 @@ impl RoaringTreemap {
         removed
     }
 
+    /// Inserts the value if it is absent from the set, removes it otherwise.
+    ///
+    /// Returns whether the value is in the set after the call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::new();
+    /// assert_eq!(rb.flip(3), true);
+    /// assert_eq!(rb.contains(3), true);
+    /// assert_eq!(rb.flip(3), false);
+    /// assert_eq!(rb.contains(3), false);
+    /// ```
+    pub fn flip(&mut self, value: u64) -> bool {
+        if self.remove(value) {
+            false
+        } else {
+            self.insert(value)
+        }
+    }
+
+    /// Toggles every value in the range, inserting the absent values and removing the
+    /// present ones.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb: RoaringTreemap = (0..10).collect();
+    /// rb.flip_range(5..15);
+    /// assert_eq!(rb, (0..5).chain(10..15).collect());
+    /// ```
+    pub fn flip_range<R>(&mut self, range: R)
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            None => return,
+        };
+
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        for hi in start_hi..=end_hi {
+            let a = if hi == start_hi { start_lo } else { 0 };
+            let b = if hi == end_hi { end_lo } else { u32::MAX };
+            match self.map.entry(hi) {
+                Entry::Vacant(entry) => {
+                    let mut rb = RoaringBitmap::new();
+                    rb.insert_range(a..=b);
+                    entry.insert(rb);
+                }
+                Entry::Occupied(mut entry) => {
+                    entry.get_mut().flip_range(a..=b);
+                    if entry.get().is_empty() {
+                        entry.remove();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the values of the range that are absent from this set,
+    /// without building the range as a treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb: RoaringTreemap = (0..10).collect();
+    /// assert_eq!(rb.complement_within(5..15), (10..15).collect());
+    /// ```
+    pub fn complement_within<R>(&self, range: R) -> RoaringTreemap
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            None => return RoaringTreemap::new(),
+        };
+
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        let mut map = BTreeMap::new();
+        for hi in start_hi..=end_hi {
+            let a = if hi == start_hi { start_lo } else { 0 };
+            let b = if hi == end_hi { end_lo } else { u32::MAX };
+            let rb = match self.map.get(&hi) {
+                Some(rb) => rb.complement_within(a..=b),
+                None => {
+                    let mut rb = RoaringBitmap::new();
+                    rb.insert_range(a..=b);
+                    rb
+                }
+            };
+            if !rb.is_empty() {
+                map.insert(hi, rb);
+            }
+        }
+
+        RoaringTreemap { map }
+    }
+
+    /// Splits the set in two at the given value, returns everything after the given value,
+    /// including the value itself.
+    ///
+    /// Only the bitmap holding the value is split, the following ones are moved
+    /// to the new set without being copied.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb: RoaringTreemap = (0..10).chain(1 << 32..(1 << 32) + 10).collect();
+    /// let split = rb.split_off(5);
+    ///
+    /// assert_eq!(rb, (0..5).collect());
+    /// assert_eq!(split, (5..10).chain(1 << 32..(1 << 32) + 10).collect());
+    /// ```
+    pub fn split_off(&mut self, at: u64) -> RoaringTreemap {
+        let (hi, lo) = util::split(at);
+        let mut map = self.map.split_off(&hi);
+
+        if let Entry::Occupied(mut ent) = map.entry(hi) {
+            if lo > 0 {
+                let upper = ent.get_mut().split_off(lo);
+                let lower = mem::replace(ent.get_mut(), upper);
+                if !lower.is_empty() {
+                    self.map.insert(hi, lower);
+                }
+                if ent.get().is_empty() {
+                    ent.remove();
+                }
+            }
+        }
+
+        RoaringTreemap { map }
+    }
+
+    /// Moves all the values of `other` at the end of this set, leaving `other` empty.
+    ///
+    /// The values of `other` must all be greater than the greatest value in the set,
+    /// the bitmaps of `other` are then moved without being copied. Otherwise nothing
+    /// is moved and an error is returned.
+    ///
+    /// Returns `Ok` with the number of values appended to the set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb: RoaringTreemap = (0..10).collect();
+    /// let mut other: RoaringTreemap = (10..20).chain(1 << 32..(1 << 32) + 10).collect();
+    ///
+    /// assert_eq!(rb.append_treemap(&mut other), Ok(20));
+    /// assert_eq!(rb, (0..20).chain(1 << 32..(1 << 32) + 10).collect());
+    /// assert!(other.is_empty());
+    ///
+    /// let mut other: RoaringTreemap = (5..15).collect();
+    /// let error = rb.append_treemap(&mut other).unwrap_err();
+    /// assert_eq!((error.max(), error.appended_min()), ((1 << 32) + 9, 5));
+    /// assert_eq!(other.len(), 10);
+    /// ```
+    pub fn append_treemap(&mut self, other: &mut RoaringTreemap) -> Result<u64, OverlappingValues> {
+        if let (Some(max), Some(min)) = (self.max(), other.min()) {
+            if min <= max {
+                return Err(OverlappingValues { max, appended_min: min });
+            }
+        }
+
+        let appended = other.len();
+        let mut map = mem::take(&mut other.map);
+        if let Some((hi, last)) = self.map.iter_mut().next_back() {
+            if let Some(mut first) = map.remove(hi) {
+                last.append_bitmap(&mut first)
+                    .expect("the values of other are greater than the maximum");
+            }
+        }
+        self.map.append(&mut map);
+
+        Ok(appended)
+    }
+
+    /// Returns a new set with every value of this set shifted by the offset,
+    /// the values shifted out of the `u64` range are dropped.
+    ///
+    /// When the offset is a multiple of 2^32 only the keys of the bitmaps are rewritten,
+    /// otherwise the values of each bitmap are split between the two neighbouring keys.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb: RoaringTreemap = [0, 10, u32::MAX as u64].into_iter().collect();
+    ///
+    /// assert_eq!(rb.add_offset(10), [10, 20, u32::MAX as u64 + 10].into_iter().collect());
+    /// assert_eq!(rb.add_offset(-10), [0, u32::MAX as u64 - 10].into_iter().collect());
+    /// ```
+    pub fn add_offset(&self, offset: i64) -> RoaringTreemap {
+        let hi_offset = offset >> 32;
+        let lo_offset = offset & i64::from(u32::MAX);
+
+        let mut map = BTreeMap::new();
+        for (&hi, bitmap) in &self.map {
+            let low = bitmap.add_offset(lo_offset);
+            let high = if lo_offset == 0 {
+                RoaringBitmap::new()
+            } else {
+                bitmap.add_offset(lo_offset - (1 << 32))
+            };
+            for (carry, shifted) in [(0, low), (1, high)] {
+                let Ok(hi) = u32::try_from(i64::from(hi) + hi_offset + carry) else { continue };
+                if !shifted.is_empty() {
+                    // The values overflowing from the previous bitmap are merged
+                    *map.entry(hi).or_insert_with(RoaringBitmap::new) |= shifted;
+                }
+            }
+        }
+
+        RoaringTreemap { map }
+    }
+
     /// Returns `true` if this set contains the specified integer.
     ///
     /// # Examples
@@ -248,6 +479,95 @@ impl RoaringTreemap {
         }
     }
 
+    /// Returns `true` if all values in the range are present in this set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::new();
+    /// // An empty range is always contained
+    /// assert!(rb.contains_range(7..7));
+    ///
+    /// rb.insert_range(1..0x1_0000_0FFF);
+    /// assert!(rb.contains_range(1..0x1_0000_0FFF));
+    /// assert!(rb.contains_range(0xFFFF_FFFF..0x1_0000_0001));
+    /// // 0 is not contained
+    /// assert!(!rb.contains_range(0..2));
+    /// // 0x1_0000_0FFF is not contained
+    /// assert!(!rb.contains_range(1..=0x1_0000_0FFF));
+    /// ```
+    pub fn contains_range<R>(&self, range: R) -> bool
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            // Empty ranges are always contained
+            None => return true,
+        };
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        // Every bitmap of the span must be present and contain its part of the range
+        let mut expected_hi = start_hi;
+        for (&hi, rb) in self.map.range(start_hi..=end_hi) {
+            if hi != expected_hi {
+                return false;
+            }
+            let a = if hi == start_hi { start_lo } else { 0 };
+            let b = if hi == end_hi { end_lo } else { u32::MAX };
+            if !rb.contains_range(a..=b) {
+                return false;
+            }
+            if hi == end_hi {
+                return true;
+            }
+            expected_hi = hi + 1;
+        }
+        false
+    }
+
+    /// Returns the number of elements in this set which are in the passed range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::new();
+    /// rb.insert_range(0x1_0000..0x4_0000);
+    /// rb.insert(0x1_0000_0001);
+    /// rb.insert(u64::MAX);
+    ///
+    /// assert_eq!(rb.range_cardinality(0..0x1_0000), 0);
+    /// assert_eq!(rb.range_cardinality(0x1_0000..0x4_0000), 0x3_0000);
+    /// assert_eq!(rb.range_cardinality(0x3_0000..0x1_0000_0002), 0x1_0001);
+    /// assert_eq!(rb.range_cardinality(0x1_0000_0000..=u64::MAX), 2);
+    /// ```
+    pub fn range_cardinality<R>(&self, range: R) -> u64
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            // Empty ranges have 0 bits set in them
+            None => return 0,
+        };
+        let (start_hi, start_lo) = util::split(start);
+        let (end_hi, end_lo) = util::split(end);
+
+        self.map
+            .range(start_hi..=end_hi)
+            .map(|(&hi, rb)| {
+                let a = if hi == start_hi { start_lo } else { 0 };
+                let b = if hi == end_hi { end_lo } else { u32::MAX };
+                rb.range_cardinality(a..=b)
+            })
+            .sum()
+    }
+
     /// Clears all integers in this set.
     ///
     /// # Examples
@@ -416,6 +736,118 @@ impl RoaringTreemap {
 
         None
     }
+
+    /// Removes the `n` smallests values from this treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::from_iter([1, 5, 1 << 32, (1 << 32) + 3]);
+    /// rb.remove_smallest(3);
+    /// assert_eq!(rb, RoaringTreemap::from_iter([(1 << 32) + 3]));
+    /// rb.remove_smallest(2);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn remove_smallest(&mut self, mut n: u64) {
+        // Find the first bitmap that is not entirely removed and remove its smallest values
+        let mut first_kept = None;
+        for (&hi, rb) in self.map.iter_mut() {
+            let len = rb.len();
+            if len <= n {
+                n -= len;
+            } else {
+                rb.remove_smallest(n);
+                first_kept = Some(hi);
+                break;
+            }
+        }
+        match first_kept {
+            Some(hi) => self.map = self.map.split_off(&hi),
+            None => self.map.clear(),
+        }
+    }
+
+    /// Removes the `n` biggests values from this treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::from_iter([1, 5, 1 << 32, (1 << 32) + 3]);
+    /// rb.remove_biggest(3);
+    /// assert_eq!(rb, RoaringTreemap::from_iter([1]));
+    /// rb.remove_biggest(2);
+    /// assert!(rb.is_empty());
+    /// ```
+    pub fn remove_biggest(&mut self, mut n: u64) {
+        // Find the last bitmap that is not entirely removed and remove its biggest values
+        let mut last_kept = None;
+        for (&hi, rb) in self.map.iter_mut().rev() {
+            let len = rb.len();
+            if len <= n {
+                n -= len;
+            } else {
+                rb.remove_biggest(n);
+                last_kept = Some(hi);
+                break;
+            }
+        }
+        match last_kept {
+            Some(hi) => {
+                if let Some(after_hi) = hi.checked_add(1) {
+                    self.map.split_off(&after_hi);
+                }
+            }
+            None => self.map.clear(),
+        }
+    }
+
+    /// Converts every container to whichever of the array, bitmap or run-length
+    /// encodings is the most compact.
+    ///
+    /// Returns `true` if at least one container changed its encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::from_iter(0..100_000);
+    /// assert!(rb.optimize());
+    /// assert!(!rb.optimize());
+    /// ```
+    pub fn optimize(&mut self) -> bool {
+        let mut changed = false;
+        for bitmap in self.map.values_mut() {
+            changed |= bitmap.optimize();
+        }
+        changed
+    }
+
+    /// Converts every run-length encoded container back to an array or a bitmap.
+    ///
+    /// Returns `true` if at least one container changed its encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let mut rb = RoaringTreemap::from_iter(0..100_000);
+    /// rb.optimize();
+    /// assert!(rb.remove_run_compression());
+    /// assert!(!rb.remove_run_compression());
+    /// ```
+    pub fn remove_run_compression(&mut self) -> bool {
+        let mut changed = false;
+        for bitmap in self.map.values_mut() {
+            changed |= bitmap.remove_run_compression();
+        }
+        changed
+    }
 }
 
 impl Default for RoaringTreemap {
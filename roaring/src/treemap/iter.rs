@@ -1,17 +1,21 @@
 use alloc::collections::{btree_map, BTreeMap};
-use core::iter;
+use core::cmp::Ordering;
+use core::iter::{self, FusedIterator};
+use core::ops::{RangeBounds, RangeInclusive};
 
 use super::util;
 use crate::bitmap::IntoIter as IntoIter32;
 use crate::bitmap::Iter as Iter32;
+use crate::bitmap::Ranges as Ranges32;
 use crate::{NonSortedIntegers, RoaringBitmap, RoaringTreemap};
 
-struct To64Iter<'a> {
+/// An iterator over the values of the bitmap of the given 32 most significant bits.
+struct To64<I> {
     hi: u32,
-    inner: Iter32<'a>,
+    inner: I,
 }
 
-impl Iterator for To64Iter<'_> {
+impl<I: Iterator<Item = u32>> Iterator for To64<I> {
     type Item = u64;
     fn next(&mut self) -> Option<u64> {
         self.inner.next().map(|n| util::join(self.hi, n))
@@ -27,7 +31,7 @@ impl Iterator for To64Iter<'_> {
     }
 }
 
-impl DoubleEndedIterator for To64Iter<'_> {
+impl<I: DoubleEndedIterator<Item = u32>> DoubleEndedIterator for To64<I> {
     fn next_back(&mut self) -> Option<Self::Item> {
         self.inner.next_back().map(|n| util::join(self.hi, n))
     }
@@ -42,61 +46,377 @@ impl DoubleEndedIterator for To64Iter<'_> {
     }
 }
 
-fn to64iter<'a>(t: (&'a u32, &'a RoaringBitmap)) -> To64Iter<'a> {
-    To64Iter { hi: *t.0, inner: t.1.iter() }
+/// The bitmap iterators that can fill a buffer with their next values.
+trait NextMany {
+    fn next_many(&mut self, dst: &mut [u32]) -> usize;
 }
 
-struct To64IntoIter {
-    hi: u32,
-    inner: IntoIter32,
+impl NextMany for Iter32<'_> {
+    fn next_many(&mut self, dst: &mut [u32]) -> usize {
+        Iter32::next_many(self, dst)
+    }
+}
+
+impl NextMany for IntoIter32 {
+    fn next_many(&mut self, dst: &mut [u32]) -> usize {
+        IntoIter32::next_many(self, dst)
+    }
+}
+
+/// The bitmap iterators that can skip the values before or after a given one.
+trait AdvanceTo {
+    fn advance_to(&mut self, n: u32);
+    fn advance_back_to(&mut self, n: u32);
+}
+
+impl AdvanceTo for Iter32<'_> {
+    fn advance_to(&mut self, n: u32) {
+        Iter32::advance_to(self, n)
+    }
+
+    fn advance_back_to(&mut self, n: u32) {
+        Iter32::advance_back_to(self, n)
+    }
+}
+
+impl AdvanceTo for IntoIter32 {
+    fn advance_to(&mut self, n: u32) {
+        IntoIter32::advance_to(self, n)
+    }
+
+    fn advance_back_to(&mut self, n: u32) {
+        IntoIter32::advance_back_to(self, n)
+    }
+}
+
+impl<I: ExactSizeIterator> To64<I> {
+    fn remaining(&self) -> u64 {
+        self.inner.len() as u64
+    }
 }
 
-impl Iterator for To64IntoIter {
+impl<I: AdvanceTo + ExactSizeIterator> To64<I> {
+    /// Advances the inner iterator and returns the number of skipped values.
+    fn advance_to(&mut self, lo: u32) -> u64 {
+        let before = self.remaining();
+        self.inner.advance_to(lo);
+        before - self.remaining()
+    }
+
+    /// Advances the back of the inner iterator and returns the number of skipped values.
+    fn advance_back_to(&mut self, lo: u32) -> u64 {
+        let before = self.remaining();
+        self.inner.advance_back_to(lo);
+        before - self.remaining()
+    }
+}
+
+impl<I: NextMany> To64<I> {
+    fn next_many(&mut self, dst: &mut [u64]) -> usize {
+        let mut buffer = [0; 256];
+        let mut count = 0;
+        while count < dst.len() {
+            let len = buffer.len().min(dst.len() - count);
+            let n = self.inner.next_many(&mut buffer[..len]);
+            for (slot, &lo) in dst[count..].iter_mut().zip(&buffer[..n]) {
+                *slot = util::join(self.hi, lo);
+            }
+            count += n;
+            if n < len {
+                break;
+            }
+        }
+        count
+    }
+}
+
+fn to64iter<'a>(t: (&'a u32, &'a RoaringBitmap)) -> To64<Iter32<'a>> {
+    To64 { hi: *t.0, inner: t.1.iter() }
+}
+
+fn to64intoiter(t: (u32, RoaringBitmap)) -> To64<IntoIter32> {
+    To64 { hi: t.0, inner: t.1.into_iter() }
+}
+
+/// Flattens the iterators of the bitmaps like `iter::FlatMap` does,
+/// but keeps the partially consumed front and back iterators reachable.
+struct Flatten<B, I> {
+    front: Option<To64<I>>,
+    bitmaps: B,
+    back: Option<To64<I>>,
+}
+
+impl<B, I> Flatten<B, I>
+where
+    B: Iterator<Item = To64<I>>,
+    I: NextMany,
+{
+    fn new(bitmaps: B) -> Flatten<B, I> {
+        Flatten { front: None, bitmaps, back: None }
+    }
+
+    fn next_many(&mut self, dst: &mut [u64]) -> usize {
+        let mut count = 0;
+        loop {
+            if let Some(iter) = &mut self.front {
+                count += iter.next_many(&mut dst[count..]);
+                if count == dst.len() {
+                    return count;
+                }
+            }
+            // The front iterator is exhausted
+            self.front = match self.bitmaps.next() {
+                Some(iter) => Some(iter),
+                None => {
+                    if let Some(iter) = &mut self.back {
+                        count += iter.next_many(&mut dst[count..]);
+                    }
+                    return count;
+                }
+            }
+        }
+    }
+}
+
+impl<B, I> Flatten<B, I>
+where
+    B: DoubleEndedIterator<Item = To64<I>>,
+    I: AdvanceTo + ExactSizeIterator,
+{
+    /// Advances the front to the first value >= `n`, returns the number of skipped values.
+    ///
+    /// The bitmaps before the one of `n` are dropped one by one, the one of `n`
+    /// is advanced with its own `advance_to`.
+    fn advance_to(&mut self, n: u64) -> u64 {
+        let (hi, lo) = util::split(n);
+        let mut skipped = 0;
+        if let Some(iter) = &mut self.front {
+            match hi.cmp(&iter.hi) {
+                Ordering::Less => return 0,
+                Ordering::Equal => return iter.advance_to(lo),
+                Ordering::Greater => {
+                    skipped += iter.remaining();
+                    self.front = None;
+                }
+            }
+        }
+        for mut iter in self.bitmaps.by_ref() {
+            match hi.cmp(&iter.hi) {
+                Ordering::Less => {
+                    self.front = Some(iter);
+                    return skipped;
+                }
+                Ordering::Equal => {
+                    skipped += iter.advance_to(lo);
+                    self.front = Some(iter);
+                    return skipped;
+                }
+                Ordering::Greater => skipped += iter.remaining(),
+            }
+        }
+        if let Some(iter) = &mut self.back {
+            match hi.cmp(&iter.hi) {
+                Ordering::Less => {}
+                Ordering::Equal => skipped += iter.advance_to(lo),
+                Ordering::Greater => {
+                    skipped += iter.remaining();
+                    self.back = None;
+                }
+            }
+        }
+        skipped
+    }
+
+    /// Advances the back to the last value <= `n`, returns the number of skipped values.
+    fn advance_back_to(&mut self, n: u64) -> u64 {
+        let (hi, lo) = util::split(n);
+        let mut skipped = 0;
+        if let Some(iter) = &mut self.back {
+            match hi.cmp(&iter.hi) {
+                Ordering::Greater => return 0,
+                Ordering::Equal => return iter.advance_back_to(lo),
+                Ordering::Less => {
+                    skipped += iter.remaining();
+                    self.back = None;
+                }
+            }
+        }
+        while let Some(mut iter) = self.bitmaps.next_back() {
+            match hi.cmp(&iter.hi) {
+                Ordering::Greater => {
+                    self.back = Some(iter);
+                    return skipped;
+                }
+                Ordering::Equal => {
+                    skipped += iter.advance_back_to(lo);
+                    self.back = Some(iter);
+                    return skipped;
+                }
+                Ordering::Less => skipped += iter.remaining(),
+            }
+        }
+        if let Some(iter) = &mut self.front {
+            match hi.cmp(&iter.hi) {
+                Ordering::Greater => {}
+                Ordering::Equal => skipped += iter.advance_back_to(lo),
+                Ordering::Less => {
+                    skipped += iter.remaining();
+                    self.front = None;
+                }
+            }
+        }
+        skipped
+    }
+}
+
+impl<B, I> Iterator for Flatten<B, I>
+where
+    B: Iterator<Item = To64<I>>,
+    I: Iterator<Item = u32>,
+{
     type Item = u64;
+
     fn next(&mut self) -> Option<u64> {
-        self.inner.next().map(|n| util::join(self.hi, n))
+        loop {
+            if let Some(iter) = &mut self.front {
+                match iter.next() {
+                    Some(value) => return Some(value),
+                    None => self.front = None,
+                }
+            }
+            match self.bitmaps.next() {
+                Some(iter) => self.front = Some(iter),
+                None => {
+                    let value = self.back.as_mut()?.next();
+                    if value.is_none() {
+                        self.back = None;
+                    }
+                    return value;
+                }
+            }
+        }
     }
 
     #[inline]
-    fn fold<B, F>(self, init: B, mut f: F) -> B
+    fn fold<Acc, F>(self, init: Acc, mut f: F) -> Acc
     where
         Self: Sized,
-        F: FnMut(B, Self::Item) -> B,
+        F: FnMut(Acc, Self::Item) -> Acc,
     {
-        self.inner.fold(init, move |b, lo| f(b, ((self.hi as u64) << 32) + (lo as u64)))
+        let mut acc = init;
+        if let Some(front) = self.front {
+            acc = front.fold(acc, &mut f);
+        }
+        acc = self.bitmaps.fold(acc, |acc, iter| iter.fold(acc, &mut f));
+        if let Some(back) = self.back {
+            acc = back.fold(acc, &mut f);
+        }
+        acc
     }
 }
 
-impl DoubleEndedIterator for To64IntoIter {
-    fn next_back(&mut self) -> Option<Self::Item> {
-        self.inner.next_back().map(|n| util::join(self.hi, n))
+impl<B, I> DoubleEndedIterator for Flatten<B, I>
+where
+    B: DoubleEndedIterator<Item = To64<I>>,
+    I: DoubleEndedIterator<Item = u32>,
+{
+    fn next_back(&mut self) -> Option<u64> {
+        loop {
+            if let Some(iter) = &mut self.back {
+                match iter.next_back() {
+                    Some(value) => return Some(value),
+                    None => self.back = None,
+                }
+            }
+            match self.bitmaps.next_back() {
+                Some(iter) => self.back = Some(iter),
+                None => {
+                    let value = self.front.as_mut()?.next_back();
+                    if value.is_none() {
+                        self.front = None;
+                    }
+                    return value;
+                }
+            }
+        }
     }
 
     #[inline]
-    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    fn rfold<Acc, F>(self, init: Acc, mut f: F) -> Acc
     where
         Self: Sized,
-        F: FnMut(B, Self::Item) -> B,
+        F: FnMut(Acc, Self::Item) -> Acc,
     {
-        self.inner.rfold(init, move |b, lo| f(b, ((self.hi as u64) << 32) + (lo as u64)))
+        let mut acc = init;
+        if let Some(back) = self.back {
+            acc = back.rfold(acc, &mut f);
+        }
+        acc = self.bitmaps.rfold(acc, |acc, iter| iter.rfold(acc, &mut f));
+        if let Some(front) = self.front {
+            acc = front.rfold(acc, &mut f);
+        }
+        acc
     }
 }
 
-fn to64intoiter(t: (u32, RoaringBitmap)) -> To64IntoIter {
-    To64IntoIter { hi: t.0, inner: t.1.into_iter() }
+#[derive(Clone)]
+struct To64Ranges<'a> {
+    hi: u32,
+    inner: Ranges32<'a>,
 }
 
-type InnerIter<'a> = iter::FlatMap<
-    btree_map::Iter<'a, u32, RoaringBitmap>,
-    To64Iter<'a>,
-    fn((&'a u32, &'a RoaringBitmap)) -> To64Iter<'a>,
+impl Iterator for To64Ranges<'_> {
+    type Item = RangeInclusive<u64>;
+    fn next(&mut self) -> Option<RangeInclusive<u64>> {
+        let range = self.inner.next()?;
+        Some(util::join(self.hi, *range.start())..=util::join(self.hi, *range.end()))
+    }
+}
+
+impl DoubleEndedIterator for To64Ranges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u64>> {
+        let range = self.inner.next_back()?;
+        Some(util::join(self.hi, *range.start())..=util::join(self.hi, *range.end()))
+    }
+}
+
+fn to64ranges<'a>(t: (&'a u32, &'a RoaringBitmap)) -> To64Ranges<'a> {
+    To64Ranges { hi: *t.0, inner: t.1.ranges() }
+}
+
+type InnerIter<'a> = Flatten<
+    iter::Map<
+        btree_map::Range<'a, u32, RoaringBitmap>,
+        fn((&'a u32, &'a RoaringBitmap)) -> To64<Iter32<'a>>,
+    >,
+    Iter32<'a>,
+>;
+type InnerIntoIter = Flatten<
+    iter::Map<
+        btree_map::IntoIter<u32, RoaringBitmap>,
+        fn((u32, RoaringBitmap)) -> To64<IntoIter32>,
+    >,
+    IntoIter32,
 >;
-type InnerIntoIter = iter::FlatMap<
-    btree_map::IntoIter<u32, RoaringBitmap>,
-    To64IntoIter,
-    fn((u32, RoaringBitmap)) -> To64IntoIter,
+
+type InnerRanges<'a> = iter::FlatMap<
+    btree_map::Iter<'a, u32, RoaringBitmap>,
+    To64Ranges<'a>,
+    fn((&'a u32, &'a RoaringBitmap)) -> To64Ranges<'a>,
 >;
 
+/// An iterator over the runs of consecutive values of a `RoaringTreemap`.
+///
+/// This struct is created by the [`RoaringTreemap::ranges`] method.
+#[derive(Clone)]
+pub struct Ranges<'a> {
+    inner: InnerRanges<'a>,
+    // The ranges of the bitmaps are merged when they touch,
+    // the ones read while looking for the end of a run are kept here.
+    peeked_front: Option<RangeInclusive<u64>>,
+    peeked_back: Option<RangeInclusive<u64>>,
+}
+
 /// An iterator for `RoaringTreemap`.
 pub struct Iter<'a> {
     inner: InnerIter<'a>,
@@ -110,19 +430,155 @@ pub struct IntoIter {
 }
 
 impl Iter<'_> {
-    fn new(map: &BTreeMap<u32, RoaringBitmap>) -> Iter {
-        let size_hint: u64 = map.iter().map(|(_, r)| r.len()).sum();
-        let i = map.iter().flat_map(to64iter as _);
+    fn new(map: &BTreeMap<u32, RoaringBitmap>) -> Iter<'_> {
+        Iter::from_range(map.range(..))
+    }
+
+    fn from_range(bitmaps: btree_map::Range<'_, u32, RoaringBitmap>) -> Iter<'_> {
+        let size_hint: u64 = bitmaps.clone().map(|(_, r)| r.len()).sum();
+        let i = Flatten::new(bitmaps.map(to64iter as _));
         Iter { inner: i, size_hint }
     }
+
+    /// Fills the buffer with the next values of the iterator and returns the number of values
+    /// written, which is lower than the length of the buffer only when the iterator is exhausted.
+    ///
+    /// This is expected to be faster than calling [`Iterator::next`] for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 3, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.iter();
+    ///
+    /// let mut buffer = [0; 4];
+    /// assert_eq!(iter.next_many(&mut buffer), 4);
+    /// assert_eq!(buffer, [1, 2, 3, 1 << 32]);
+    /// assert_eq!(iter.next_many(&mut buffer), 1);
+    /// assert_eq!(buffer[0], (1 << 32) + 1);
+    /// ```
+    pub fn next_many(&mut self, dst: &mut [u64]) -> usize {
+        let count = self.inner.next_many(dst);
+        self.size_hint = self.size_hint.saturating_sub(count as u64);
+        count
+    }
+
+    /// Advance the iterator to the first position where the item has a value >= `n`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.iter();
+    /// iter.advance_to(3);
+    ///
+    /// assert_eq!(iter.next(), Some(1 << 32));
+    /// assert_eq!(iter.next(), Some((1 << 32) + 1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn advance_to(&mut self, n: u64) {
+        let skipped = self.inner.advance_to(n);
+        self.size_hint = self.size_hint.saturating_sub(skipped);
+    }
+
+    /// Advance the back of the iterator to the first position where the item has a value <= `n`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.iter();
+    /// iter.advance_back_to(1 << 32);
+    ///
+    /// assert_eq!(iter.next_back(), Some(1 << 32));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// assert_eq!(iter.next_back(), Some(1));
+    /// assert_eq!(iter.next_back(), None);
+    /// ```
+    pub fn advance_back_to(&mut self, n: u64) {
+        let skipped = self.inner.advance_back_to(n);
+        self.size_hint = self.size_hint.saturating_sub(skipped);
+    }
 }
 
 impl IntoIter {
     fn new(map: BTreeMap<u32, RoaringBitmap>) -> IntoIter {
         let size_hint = map.values().map(|r| r.len()).sum();
-        let i = map.into_iter().flat_map(to64intoiter as _);
+        let i = Flatten::new(map.into_iter().map(to64intoiter as _));
         IntoIter { inner: i, size_hint }
     }
+
+    /// Fills the buffer with the next values of the iterator and returns the number of values
+    /// written, which is lower than the length of the buffer only when the iterator is exhausted.
+    ///
+    /// This is expected to be faster than calling [`Iterator::next`] for each value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 3, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.into_iter();
+    ///
+    /// let mut buffer = [0; 4];
+    /// assert_eq!(iter.next_many(&mut buffer), 4);
+    /// assert_eq!(buffer, [1, 2, 3, 1 << 32]);
+    /// assert_eq!(iter.next_many(&mut buffer), 1);
+    /// assert_eq!(buffer[0], (1 << 32) + 1);
+    /// ```
+    pub fn next_many(&mut self, dst: &mut [u64]) -> usize {
+        let count = self.inner.next_many(dst);
+        self.size_hint = self.size_hint.saturating_sub(count as u64);
+        count
+    }
+
+    /// Advance the iterator to the first position where the item has a value >= `n`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.into_iter();
+    /// iter.advance_to(3);
+    ///
+    /// assert_eq!(iter.next(), Some(1 << 32));
+    /// assert_eq!(iter.next(), Some((1 << 32) + 1));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn advance_to(&mut self, n: u64) {
+        let skipped = self.inner.advance_to(n);
+        self.size_hint = self.size_hint.saturating_sub(skipped);
+    }
+
+    /// Advance the back of the iterator to the first position where the item has a value <= `n`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 1 << 32, (1 << 32) + 1]);
+    /// let mut iter = treemap.into_iter();
+    /// iter.advance_back_to(1 << 32);
+    ///
+    /// assert_eq!(iter.next_back(), Some(1 << 32));
+    /// assert_eq!(iter.next_back(), Some(2));
+    /// assert_eq!(iter.next_back(), Some(1));
+    /// assert_eq!(iter.next_back(), None);
+    /// ```
+    pub fn advance_back_to(&mut self, n: u64) {
+        let skipped = self.inner.advance_back_to(n);
+        self.size_hint = self.size_hint.saturating_sub(skipped);
+    }
 }
 
 impl Iterator for Iter<'_> {
@@ -221,6 +677,58 @@ impl ExactSizeIterator for IntoIter {
     }
 }
 
+impl Ranges<'_> {
+    fn new(map: &BTreeMap<u32, RoaringBitmap>) -> Ranges<'_> {
+        Ranges {
+            inner: map.iter().flat_map(to64ranges as _),
+            peeked_front: None,
+            peeked_back: None,
+        }
+    }
+}
+
+impl Iterator for Ranges<'_> {
+    type Item = RangeInclusive<u64>;
+
+    fn next(&mut self) -> Option<RangeInclusive<u64>> {
+        let mut range = self
+            .peeked_front
+            .take()
+            .or_else(|| self.inner.next())
+            .or_else(|| self.peeked_back.take())?;
+        while let Some(next) = self.inner.next().or_else(|| self.peeked_back.take()) {
+            if *next.start() - 1 == *range.end() {
+                range = *range.start()..=*next.end();
+            } else {
+                self.peeked_front = Some(next);
+                break;
+            }
+        }
+        Some(range)
+    }
+}
+
+impl DoubleEndedIterator for Ranges<'_> {
+    fn next_back(&mut self) -> Option<RangeInclusive<u64>> {
+        let mut range = self
+            .peeked_back
+            .take()
+            .or_else(|| self.inner.next_back())
+            .or_else(|| self.peeked_front.take())?;
+        while let Some(previous) = self.inner.next_back().or_else(|| self.peeked_front.take()) {
+            if *previous.end() + 1 == *range.start() {
+                range = *previous.start()..=*range.end();
+            } else {
+                self.peeked_back = Some(previous);
+                break;
+            }
+        }
+        Some(range)
+    }
+}
+
+impl FusedIterator for Ranges<'_> {}
+
 impl RoaringTreemap {
     /// Iterator over each value stored in the RoaringTreemap, guarantees values are ordered by
     /// value.
@@ -238,10 +746,103 @@ impl RoaringTreemap {
     /// assert_eq!(iter.next(), Some(2));
     /// assert_eq!(iter.next(), None);
     /// ```
-    pub fn iter(&self) -> Iter {
+    pub fn iter(&self) -> Iter<'_> {
         Iter::new(&self.map)
     }
 
+    /// Iterator over values within a range stored in the RoaringTreemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::ops::Bound;
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([0, 1, 2, 10, 11, 1 << 32, u64::MAX]);
+    /// let mut iter = treemap.range(10..20);
+    ///
+    /// assert_eq!(iter.next(), Some(10));
+    /// assert_eq!(iter.next(), Some(11));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let mut iter = treemap.range(100..);
+    /// assert_eq!(iter.next(), Some(1 << 32));
+    /// assert_eq!(iter.next(), Some(u64::MAX));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let mut iter = treemap.range((Bound::Excluded(0), Bound::Included(10)));
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), Some(10));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn range<R>(&self, range: R) -> Iter<'_>
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            None => return Iter::from_range(self.map.range(0..0)),
+        };
+        let (start_hi, _) = util::split(start);
+        let (end_hi, _) = util::split(end);
+        let mut iter = Iter::from_range(self.map.range(start_hi..=end_hi));
+        iter.advance_to(start);
+        iter.advance_back_to(end);
+        iter
+    }
+
+    /// Iterator over values within a range stored in the RoaringTreemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use core::ops::Bound;
+    /// use roaring::RoaringTreemap;
+    ///
+    /// fn treemap() -> RoaringTreemap {
+    ///     RoaringTreemap::from([0, 1, 2, 10, 11, 1 << 32, u64::MAX])
+    /// }
+    ///
+    /// let mut iter = treemap().into_range(10..20);
+    ///
+    /// assert_eq!(iter.next(), Some(10));
+    /// assert_eq!(iter.next(), Some(11));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let mut iter = treemap().into_range(100..);
+    /// assert_eq!(iter.next(), Some(1 << 32));
+    /// assert_eq!(iter.next(), Some(u64::MAX));
+    /// assert_eq!(iter.next(), None);
+    ///
+    /// let mut iter = treemap().into_range((Bound::Excluded(0), Bound::Included(10)));
+    /// assert_eq!(iter.next(), Some(1));
+    /// assert_eq!(iter.next(), Some(2));
+    /// assert_eq!(iter.next(), Some(10));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    pub fn into_range<R>(self, range: R) -> IntoIter
+    where
+        R: RangeBounds<u64>,
+    {
+        let (start, end) = match util::convert_range_to_inclusive(range) {
+            Some(range) => (*range.start(), *range.end()),
+            None => return IntoIter::new(BTreeMap::new()),
+        };
+        let (start_hi, _) = util::split(start);
+        let (end_hi, _) = util::split(end);
+        // Only keep the bitmaps overlapping the range before building the iterator
+        let mut map = self.map;
+        let mut map = map.split_off(&start_hi);
+        if let Some(after_end_hi) = end_hi.checked_add(1) {
+            map.split_off(&after_end_hi);
+        }
+        let mut iter = IntoIter::new(map);
+        iter.advance_to(start);
+        iter.advance_back_to(end);
+        iter
+    }
+
     /// Iterator over pairs of partition number and the corresponding RoaringBitmap.
     /// The partition number is defined by the 32 most significant bits of the bit index.
     ///
@@ -257,10 +858,30 @@ impl RoaringTreemap {
     /// assert_eq!(bitmaps.next(), Some((0, &(0..6000).collect::<RoaringBitmap>())));
     /// assert_eq!(bitmaps.next(), None);
     /// ```
-    pub fn bitmaps(&self) -> BitmapIter {
+    pub fn bitmaps(&self) -> BitmapIter<'_> {
         BitmapIter(self.map.iter())
     }
 
+    /// Iterator over the maximal runs of consecutive values stored in the RoaringTreemap,
+    /// guarantees runs are ordered by value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap = RoaringTreemap::from([1, 2, 3, 7, u32::MAX as u64, 1 << 32]);
+    /// let mut ranges = treemap.ranges();
+    ///
+    /// assert_eq!(ranges.next(), Some(1..=3));
+    /// assert_eq!(ranges.next_back(), Some(u32::MAX as u64..=1 << 32));
+    /// assert_eq!(ranges.next(), Some(7..=7));
+    /// assert_eq!(ranges.next(), None);
+    /// ```
+    pub fn ranges(&self) -> Ranges<'_> {
+        Ranges::new(&self.map)
+    }
+
     /// Construct a RoaringTreemap from an iterator of partition number and RoaringBitmap pairs.
     /// The partition number is defined by the 32 most significant bits of the bit index.
     /// Note that repeated partitions, if present, will replace previously set partitions.
@@ -3,6 +3,7 @@ use alloc::collections::BTreeMap;
 
 mod fmt;
 mod multiops;
+mod statistics;
 mod util;
 
 // Order of these modules matters as it determines the `impl` blocks order in
@@ -12,12 +13,15 @@ mod cmp;
 mod inherent;
 mod iter;
 mod ops;
+#[cfg(feature = "std")]
+mod ops_with_serialized;
 #[cfg(feature = "serde")]
 mod serde;
 #[cfg(feature = "std")]
 mod serialization;
 
-pub use self::iter::{IntoIter, Iter};
+pub use self::iter::{IntoIter, Iter, Ranges};
+pub use self::statistics::Statistics;
 
 /// A compressed bitmap with u64 values.
 /// Implemented as a `BTreeMap` of `RoaringBitmap`s.
@@ -103,6 +103,126 @@ impl RoaringTreemap {
             .wrapping_sub(intersection_len)
             .wrapping_sub(intersection_len)
     }
+
+    /// Computes the [Jaccard index](https://en.wikipedia.org/wiki/Jaccard_index) with the
+    /// specified other treemap, the len of the intersection divided by the len of the union,
+    /// in a single pass and without creating a new treemap.
+    ///
+    /// Returns `NaN` when both sets are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb1: RoaringTreemap = (1..4).collect();
+    /// let rb2: RoaringTreemap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.jaccard_index(&rb2), 0.25);
+    /// ```
+    pub fn jaccard_index(&self, other: &RoaringTreemap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        let (lhs_len, rhs_len, intersection_len) =
+            (lhs_len as f64, rhs_len as f64, intersection_len as f64);
+        intersection_len / (lhs_len + rhs_len - intersection_len)
+    }
+
+    /// Computes the [Sørensen–Dice coefficient](https://en.wikipedia.org/wiki/Dice-S%C3%B8rensen_coefficient)
+    /// with the specified other treemap, twice the len of the intersection divided by the sum of
+    /// the lens, in a single pass and without creating a new treemap.
+    ///
+    /// Returns `NaN` when both sets are empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb1: RoaringTreemap = (1..4).collect();
+    /// let rb2: RoaringTreemap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.dice(&rb2), 0.4);
+    /// ```
+    pub fn dice(&self, other: &RoaringTreemap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        2.0 * intersection_len as f64 / (lhs_len as f64 + rhs_len as f64)
+    }
+
+    /// Computes the [cosine similarity](https://en.wikipedia.org/wiki/Cosine_similarity) with
+    /// the specified other treemap, the len of the intersection divided by the geometric mean of
+    /// the lens, in a single pass and without creating a new treemap.
+    ///
+    /// Returns `NaN` when one of the sets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb1: RoaringTreemap = (1..5).collect();
+    /// let rb2: RoaringTreemap = (4..5).collect();
+    ///
+    /// assert_eq!(rb1.cosine(&rb2), 0.5);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn cosine(&self, other: &RoaringTreemap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        intersection_len as f64 / (lhs_len as f64 * rhs_len as f64).sqrt()
+    }
+
+    /// Computes the [overlap coefficient](https://en.wikipedia.org/wiki/Overlap_coefficient)
+    /// with the specified other treemap, the len of the intersection divided by the len of the
+    /// smallest set, in a single pass and without creating a new treemap.
+    ///
+    /// Returns `NaN` when one of the sets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb1: RoaringTreemap = (1..4).collect();
+    /// let rb2: RoaringTreemap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.overlap_coefficient(&rb2), 0.5);
+    /// ```
+    pub fn overlap_coefficient(&self, other: &RoaringTreemap) -> f64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        intersection_len as f64 / lhs_len.min(rhs_len) as f64
+    }
+
+    /// Computes the [Hamming distance](https://en.wikipedia.org/wiki/Hamming_distance) with the
+    /// specified other treemap, the number of values contained in only one of the sets, in a
+    /// single pass and without creating a new treemap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let rb1: RoaringTreemap = (1..4).collect();
+    /// let rb2: RoaringTreemap = (3..5).collect();
+    ///
+    /// assert_eq!(rb1.hamming_distance(&rb2), rb1.symmetric_difference_len(&rb2));
+    /// ```
+    pub fn hamming_distance(&self, other: &RoaringTreemap) -> u64 {
+        let (lhs_len, rhs_len, intersection_len) = self.lens_and_intersection_len(other);
+        lhs_len.wrapping_add(rhs_len).wrapping_sub(intersection_len).wrapping_sub(intersection_len)
+    }
+
+    /// Computes the lens of both sets and the len of their intersection in a single pass.
+    fn lens_and_intersection_len(&self, other: &RoaringTreemap) -> (u64, u64, u64) {
+        self.pairs(other)
+            .map(|pair| match pair {
+                (Some(lhs), None) => (lhs.len(), 0, 0),
+                (None, Some(rhs)) => (0, rhs.len(), 0),
+                (Some(lhs), Some(rhs)) => lhs.lens_and_intersection_len(rhs),
+                (None, None) => (0, 0, 0),
+            })
+            .fold((0, 0, 0), |(a, b, c), (x, y, z)| {
+                (a.wrapping_add(x), b.wrapping_add(y), c.wrapping_add(z))
+            })
+    }
 }
 
 impl BitOr<RoaringTreemap> for RoaringTreemap {
@@ -407,6 +527,23 @@ mod test {
     use crate::{MultiOps, RoaringTreemap};
     use proptest::prelude::*;
 
+    proptest! {
+        #[test]
+        fn flip_range_eq_symmetric_difference_with_range(
+            a in RoaringTreemap::arbitrary(),
+            hi in 0u64..=16,
+            lo in prop_oneof![0u64..0x12_0000, 0xFFFF_0000u64..0x1_0000_0000],
+            len in 0u64..0x3_0000,
+        ) {
+            let start = (hi << 32) + lo;
+            let range: RoaringTreemap = (start..start + len).collect();
+            let mut flipped = a.clone();
+            flipped.flip_range(start..start + len);
+            prop_assert_eq!(&flipped, &(&a ^ &range));
+            prop_assert_eq!(a.complement_within(start..start + len), &range - &a);
+        }
+    }
+
     // fast count tests
     proptest! {
         #[test]
@@ -441,6 +578,22 @@ mod test {
             prop_assert_eq!(a.symmetric_difference_len(&b), (a ^ b).len());
         }
 
+        #[test]
+        fn similarities_eq_ratios_of_materialized_lens(
+            a in RoaringTreemap::arbitrary(),
+            b in RoaringTreemap::arbitrary()
+        ) {
+            let intersection_len = (&a & &b).len() as f64;
+            let (a_len, b_len) = (a.len() as f64, b.len() as f64);
+            let eq = |lhs: f64, rhs: f64| lhs == rhs || (lhs.is_nan() && rhs.is_nan());
+            prop_assert!(eq(a.jaccard_index(&b), intersection_len / (&a | &b).len() as f64));
+            prop_assert!(eq(a.dice(&b), 2.0 * intersection_len / (a_len + b_len)));
+            #[cfg(feature = "std")]
+            prop_assert!(eq(a.cosine(&b), intersection_len / (a_len * b_len).sqrt()));
+            prop_assert!(eq(a.overlap_coefficient(&b), intersection_len / a_len.min(b_len)));
+            prop_assert_eq!(a.hamming_distance(&b), (a ^ b).len());
+        }
+
         #[test]
         fn all_union_give_the_same_result(
             a in RoaringTreemap::arbitrary(),
@@ -0,0 +1,74 @@
+use alloc::collections::BTreeMap;
+use byteorder::{LittleEndian, ReadBytesExt};
+use std::io;
+
+use crate::{RoaringBitmap, RoaringTreemap};
+
+impl RoaringTreemap {
+    /// Computes the intersection between a materialized [`RoaringTreemap`] and a serialized one.
+    ///
+    /// This is faster and more space efficient when you only need the intersection result.
+    /// Each serialized bitmap is intersected with the bitmap of the same high key
+    /// with [`RoaringBitmap::intersection_with_serialized_unchecked`], the other ones are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use roaring::RoaringTreemap;
+    /// use std::io::Cursor;
+    ///
+    /// let rt1: RoaringTreemap = (1..4).chain(1 << 32..(1 << 32) + 4).collect();
+    /// let rt2: RoaringTreemap = (3..5).chain((1 << 32) + 3..(1 << 32) + 10).collect();
+    ///
+    /// // Let's say the rt2 treemap is serialized
+    /// let mut bytes = Vec::new();
+    /// rt2.serialize_into(&mut bytes).unwrap();
+    /// let rt2_bytes = Cursor::new(bytes);
+    ///
+    /// assert_eq!(
+    ///     rt1.intersection_with_serialized_unchecked(rt2_bytes).unwrap(),
+    ///     rt1 & rt2,
+    /// );
+    /// ```
+    pub fn intersection_with_serialized_unchecked<R>(&self, mut other: R) -> io::Result<Self>
+    where
+        R: io::Read + io::Seek,
+    {
+        let empty = RoaringBitmap::new();
+        let size = other.read_u64::<LittleEndian>()?;
+
+        let mut map = BTreeMap::new();
+        for _ in 0..size {
+            let key = other.read_u32::<LittleEndian>()?;
+            // Intersecting with an empty bitmap only skips the serialized one
+            let bitmap = self.map.get(&key).unwrap_or(&empty);
+            let intersection = bitmap.intersection_with_serialized_unchecked(&mut other)?;
+            if !intersection.is_empty() {
+                map.insert(key, intersection);
+            }
+        }
+
+        Ok(RoaringTreemap { map })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RoaringTreemap;
+    use proptest::prelude::*;
+    use std::io::Cursor;
+
+    proptest! {
+        #[test]
+        fn intersection_with_serialized_eq_materialized_intersection(
+            a in RoaringTreemap::arbitrary(),
+            b in RoaringTreemap::arbitrary()
+        ) {
+            let mut serialized_bytes_b = Vec::new();
+            b.serialize_into(&mut serialized_bytes_b).unwrap();
+            let serialized_bytes_b = &serialized_bytes_b[..];
+
+            prop_assert_eq!(a.intersection_with_serialized_unchecked(Cursor::new(serialized_bytes_b)).unwrap(), a & b);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use super::RoaringTreemap;
+use crate::bitmap::DeserializeError;
 use crate::RoaringBitmap;
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 use std::{io, mem::size_of};
@@ -100,7 +101,7 @@ impl RoaringTreemap {
     fn deserialize_from_impl<R, F>(mut reader: R, mut deserialize_bitmap: F) -> io::Result<Self>
     where
         R: io::Read,
-        F: FnMut(&mut R) -> io::Result<RoaringBitmap>,
+        F: FnMut(&mut R) -> Result<RoaringBitmap, DeserializeError>,
     {
         let size = reader.read_u64::<LittleEndian>()?;
 
@@ -0,0 +1,95 @@
+use crate::RoaringTreemap;
+
+/// Detailed statistics on the composition of a treemap.
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[non_exhaustive]
+pub struct Statistics {
+    /// Number of bitmaps in the treemap
+    pub n_bitmaps: u64,
+    /// Number of containers in the treemap
+    pub n_containers: u64,
+    /// Number of array containers in the treemap
+    pub n_array_containers: u64,
+    /// Number of run containers in the treemap
+    pub n_run_containers: u64,
+    /// Number of bitset containers in the treemap
+    pub n_bitset_containers: u64,
+    /// Number of values stored in array containers
+    pub n_values_array_containers: u64,
+    /// Number of values stored in run containers
+    pub n_values_run_containers: u64,
+    /// Number of values stored in bitset containers
+    pub n_values_bitset_containers: u64,
+    /// Number of bytes used by array containers
+    pub n_bytes_array_containers: u64,
+    /// Number of bytes used by run containers
+    pub n_bytes_run_containers: u64,
+    /// Number of bytes used by bitset containers
+    pub n_bytes_bitset_containers: u64,
+    /// Maximum value stored in the treemap
+    pub max_value: Option<u64>,
+    /// Minimum value stored in the treemap
+    pub min_value: Option<u64>,
+    /// Number of values stored in the treemap
+    pub cardinality: u64,
+}
+
+impl RoaringTreemap {
+    /// Returns statistics about the composition of a roaring treemap,
+    /// summing the statistics of its bitmaps.
+    ///
+    /// ```
+    /// use roaring::RoaringTreemap;
+    ///
+    /// let treemap: RoaringTreemap = (1..100).chain(1 << 32..(1 << 32) + 5000).collect();
+    /// let statistics = treemap.statistics();
+    ///
+    /// assert_eq!(statistics.n_bitmaps, 2);
+    /// assert_eq!(statistics.n_containers, 2);
+    /// assert_eq!(statistics.n_array_containers, 1);
+    /// assert_eq!(statistics.n_run_containers, 0);
+    /// assert_eq!(statistics.n_bitset_containers, 1);
+    /// assert_eq!(statistics.n_values_array_containers, 99);
+    /// assert_eq!(statistics.n_values_run_containers, 0);
+    /// assert_eq!(statistics.n_values_bitset_containers, 5000);
+    /// assert_eq!(statistics.max_value, Some((1 << 32) + 4999));
+    /// assert_eq!(statistics.min_value, Some(1));
+    /// assert_eq!(statistics.cardinality, 5099);
+    /// ```
+    pub fn statistics(&self) -> Statistics {
+        let mut statistics = Statistics {
+            n_bitmaps: 0,
+            n_containers: 0,
+            n_array_containers: 0,
+            n_run_containers: 0,
+            n_bitset_containers: 0,
+            n_values_array_containers: 0,
+            n_values_run_containers: 0,
+            n_values_bitset_containers: 0,
+            n_bytes_array_containers: 0,
+            n_bytes_run_containers: 0,
+            n_bytes_bitset_containers: 0,
+            max_value: self.max(),
+            min_value: self.min(),
+            cardinality: 0,
+        };
+
+        for bitmap in self.map.values() {
+            let bitmap = bitmap.statistics();
+            statistics.n_bitmaps += 1;
+            statistics.n_containers += u64::from(bitmap.n_containers);
+            statistics.n_array_containers += u64::from(bitmap.n_array_containers);
+            statistics.n_run_containers += u64::from(bitmap.n_run_containers);
+            statistics.n_bitset_containers += u64::from(bitmap.n_bitset_containers);
+            statistics.n_values_array_containers += u64::from(bitmap.n_values_array_containers);
+            statistics.n_values_run_containers += u64::from(bitmap.n_values_run_containers);
+            statistics.n_values_bitset_containers += bitmap.n_values_bitset_containers;
+            statistics.n_bytes_array_containers += bitmap.n_bytes_array_containers;
+            statistics.n_bytes_run_containers += bitmap.n_bytes_run_containers;
+            statistics.n_bytes_bitset_containers += bitmap.n_bytes_bitset_containers;
+            statistics.cardinality += bitmap.cardinality;
+        }
+
+        statistics
+    }
+}
@@ -1,6 +1,7 @@
 use proptest::arbitrary::any;
-use proptest::collection::btree_set;
+use proptest::collection::{btree_set, vec};
 use proptest::proptest;
+use std::ops::RangeInclusive;
 
 use roaring::RoaringBitmap;
 
@@ -218,3 +219,184 @@ proptest! {
         assert!(outside_in(values).eq(outside_in(bitmap)));
     }
 }
+
+pub fn naive_ranges<T>(values: impl IntoIterator<Item = T>) -> Vec<RangeInclusive<T>>
+where
+    T: Copy + PartialEq + core::ops::Add<Output = T> + From<u8>,
+{
+    let mut ranges: Vec<RangeInclusive<T>> = Vec::new();
+    for value in values {
+        match ranges.last_mut() {
+            Some(last) if *last.end() + T::from(1) == value => *last = *last.start()..=value,
+            _ => ranges.push(value..=value),
+        }
+    }
+    ranges
+}
+
+#[test]
+fn ranges() {
+    let mut bitmap = RoaringBitmap::new();
+    bitmap.insert_range(10..20);
+    bitmap.insert_range(60_000..70_000);
+    bitmap.extend((0x2_0000..0x3_0000).filter(|v| v % 3 != 0));
+    bitmap.insert_range(0x3_0000..0x5_0010);
+    bitmap.insert(u32::MAX);
+    let expected = naive_ranges(bitmap.iter());
+
+    assert!(bitmap.ranges().eq(expected.iter().cloned()));
+    assert!(bitmap.ranges().rev().eq(expected.iter().rev().cloned()));
+    assert!(outside_in(bitmap.ranges()).eq(outside_in(expected.iter().cloned())));
+
+    bitmap.optimize();
+    assert!(bitmap.ranges().eq(expected.iter().cloned()));
+    assert!(bitmap.ranges().rev().eq(expected.iter().rev().cloned()));
+    assert!(outside_in(bitmap.ranges()).eq(outside_in(expected)));
+}
+
+proptest! {
+    #[test]
+    fn ranges_iter(values in btree_set(0u32..300_000, ..=100_000), optimize in any::<bool>()) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        if optimize {
+            bitmap.optimize();
+        }
+        let expected = naive_ranges(values);
+
+        assert!(bitmap.ranges().eq(expected.iter().cloned()));
+        assert!(bitmap.ranges().rev().eq(expected.iter().rev().cloned()));
+        assert!(outside_in(bitmap.ranges()).eq(outside_in(expected)));
+    }
+}
+
+#[test]
+fn from_sorted_ranges() {
+    let ranges = [0..=9, 10..=19, 65_530..=65_545, 100_000..=300_000, u32::MAX..=u32::MAX];
+    let bitmap = RoaringBitmap::from_sorted_ranges(ranges.clone()).unwrap();
+    let expected = ranges.iter().cloned().flatten().collect::<RoaringBitmap>();
+    assert_eq!(bitmap, expected);
+    assert!(bitmap.ranges().eq([0..=19, 65_530..=65_545, 100_000..=300_000, u32::MAX..=u32::MAX]));
+
+    let error = RoaringBitmap::from_sorted_ranges([0..=9, 5..=19]).unwrap_err();
+    assert_eq!(error.valid_until(), 1);
+}
+
+#[test]
+fn extend_ranges() {
+    let mut bitmap = RoaringBitmap::from([1, 2, 3, 70_000]);
+    // appended to the last container, then out of order
+    bitmap.extend([70_002..=80_000, 90_000..=90_010, 5..=6, 65_535..=70_001]);
+
+    let mut expected = RoaringBitmap::from([1, 2, 3]);
+    expected.insert_range(5..=6);
+    expected.insert_range(65_535..=80_000);
+    expected.insert_range(90_000..=90_010);
+    assert_eq!(bitmap, expected);
+}
+
+proptest! {
+    #[test]
+    fn from_sorted_ranges_iter(bounds in btree_set(0u32..400_000, ..=2_000)) {
+        let bounds: Vec<u32> = bounds.into_iter().collect();
+        let ranges: Vec<RangeInclusive<u32>> =
+            bounds.chunks_exact(2).map(|pair| pair[0]..=pair[1]).collect();
+        let expected = ranges.iter().cloned().flatten().collect::<RoaringBitmap>();
+
+        assert_eq!(RoaringBitmap::from_sorted_ranges(ranges.iter().cloned()).unwrap(), expected.clone());
+        assert_eq!(ranges.iter().rev().cloned().collect::<RoaringBitmap>(), expected);
+    }
+
+    #[test]
+    fn from_unsorted_ranges_iter(ranges in vec((0u32..400_000, 0u32..70_000), ..=200)) {
+        let ranges: Vec<RangeInclusive<u32>> =
+            ranges.into_iter().map(|(start, len)| start..=start + len).collect();
+        let mut expected = RoaringBitmap::new();
+        for range in &ranges {
+            expected.insert_range(range.clone());
+        }
+
+        assert_eq!(ranges.into_iter().collect::<RoaringBitmap>(), expected);
+    }
+}
+
+#[test]
+fn from_unsorted_slice() {
+    // more values than the sorting threshold, with dense and sparse containers
+    let values: Vec<u32> =
+        (0..200_000u32).map(|i| i.wrapping_mul(2_654_435_761) % 300_000).collect();
+    let expected: RoaringBitmap = values.iter().collect();
+    assert_eq!(RoaringBitmap::from_unsorted_slice(&values), expected);
+
+    let initial = RoaringBitmap::from([1, 70_000, 400_000, u32::MAX]);
+    let mut bitmap = initial.clone();
+    assert_eq!(bitmap.extend_from_slice(&values), (&expected | &initial).len() - initial.len());
+    assert_eq!(bitmap, expected | initial);
+}
+
+proptest! {
+    #[test]
+    fn from_unsorted_slice_iter(
+        values in vec(0u32..400_000, ..=10_000),
+        initial in btree_set(any::<u32>(), ..=1_000),
+    ) {
+        let expected: RoaringBitmap = values.iter().collect();
+        assert_eq!(RoaringBitmap::from_unsorted_slice(&values), expected.clone());
+
+        let initial: RoaringBitmap = initial.into_iter().collect();
+        let mut bitmap = initial.clone();
+        let inserted = bitmap.extend_from_slice(&values);
+        assert_eq!(inserted, expected.len() - expected.intersection_len(&initial));
+        assert_eq!(bitmap, expected | initial);
+    }
+}
+
+/// Reads the iterator with `next_many` in chunks of `len` values.
+fn read_many(mut next_many: impl FnMut(&mut [u32]) -> usize, len: usize) -> Vec<u32> {
+    let mut values = Vec::new();
+    let mut buffer = vec![0; len];
+    loop {
+        let n = next_many(&mut buffer);
+        values.extend_from_slice(&buffer[..n]);
+        if n < len {
+            return values;
+        }
+    }
+}
+
+proptest! {
+    #[test]
+    fn next_many(
+        values in btree_set(0u32..300_000, ..=50_000),
+        optimize in any::<bool>(),
+        len in 1usize..5_000,
+        from in 0u32..300_000,
+        back in 0usize..100,
+    ) {
+        let mut bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        if optimize {
+            bitmap.optimize();
+        }
+        let expected: Vec<u32> = values.range(from..).cloned().collect();
+        let expected = &expected[..expected.len().saturating_sub(back)];
+
+        let mut iter = bitmap.iter();
+        iter.advance_to(from);
+        iter.by_ref().rev().take(back).for_each(drop);
+        assert_eq!(read_many(|dst| iter.next_many(dst), len), expected);
+        assert_eq!(iter.next(), None);
+
+        let mut iter = bitmap.clone().into_iter();
+        iter.advance_to(from);
+        iter.by_ref().rev().take(back).for_each(drop);
+        assert_eq!(read_many(|dst| iter.next_many(dst), len), expected);
+        assert_eq!(iter.next(), None);
+
+        // values read one by one are not read again
+        let mut iter = bitmap.iter();
+        iter.advance_to(from);
+        let first = iter.next();
+        let mut rest = vec![0; values.len()];
+        let n = iter.next_many(&mut rest);
+        assert_eq!(first.into_iter().chain(rest[..n].iter().cloned()).collect::<Vec<_>>(), values.range(from..).cloned().collect::<Vec<_>>());
+    }
+}
@@ -72,3 +72,18 @@ proptest! {
         }
     }
 }
+
+proptest! {
+    #[test]
+    fn proptest_rank_many(
+        values in btree_set(..=262_143_u32, ..=1000),
+        mut checks in vec(..=262_143_u32, ..=100),
+        sorted in any::<bool>(),
+    ){
+        let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        if sorted {
+            checks.sort_unstable();
+        }
+        prop_assert!(bitmap.rank_many(&checks).eq(checks.iter().map(|&i| bitmap.rank(i))));
+    }
+}
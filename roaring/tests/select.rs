@@ -1,6 +1,6 @@
 extern crate roaring;
 
-use proptest::collection::btree_set;
+use proptest::collection::{btree_set, vec};
 use proptest::prelude::*;
 use roaring::RoaringBitmap;
 
@@ -50,3 +50,18 @@ proptest! {
         }
     }
 }
+
+proptest! {
+    #[test]
+    fn proptest_select_many(
+        values in btree_set(any::<u32>(), ..=1000),
+        mut ranks in vec(..=1100_u32, ..=100),
+        sorted in any::<bool>(),
+    ) {
+        let bitmap = RoaringBitmap::from_sorted_iter(values.iter().cloned()).unwrap();
+        if sorted {
+            ranks.sort_unstable();
+        }
+        prop_assert!(bitmap.select_many(&ranks).eq(ranks.iter().map(|&n| bitmap.select(n))));
+    }
+}
@@ -44,6 +44,37 @@ fn test_serialize_into_provided_data() {
     assert!(BITMAP_WITHOUT_RUNS == &buffer[..]);
 }
 
+#[test]
+fn test_serialize_with_runs_into_provided_data() {
+    let mut bitmap = test_data_bitmap();
+    assert!(bitmap.optimize());
+    let mut buffer = vec![];
+    bitmap.serialize_into(&mut buffer).unwrap();
+    assert_eq!(buffer.len(), bitmap.serialized_size());
+    assert!(BITMAP_WITH_RUNS == &buffer[..]);
+}
+
+#[test]
+fn test_runs() {
+    let mut original = RoaringBitmap::new();
+    original.insert_range(10..20_000);
+    original.insert_range(70_000..140_000);
+    original.insert(u32::MAX);
+    original.optimize();
+    let new = serialize_and_deserialize(&original);
+    assert_eq!(original, new);
+    assert_eq!(new.statistics().n_run_containers, 3);
+}
+
+#[test]
+fn test_runs_with_offsets() {
+    let mut original = (0..10).flat_map(|i| i << 16..(i << 16) + 100).collect::<RoaringBitmap>();
+    original.insert(1 << 20);
+    original.optimize();
+    let new = serialize_and_deserialize(&original);
+    assert_eq!(original, new);
+}
+
 #[test]
 fn test_empty() {
     let original = RoaringBitmap::new();
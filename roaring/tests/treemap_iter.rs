@@ -2,7 +2,7 @@ extern crate roaring;
 mod iter;
 use roaring::RoaringTreemap;
 
-use iter::outside_in;
+use iter::{naive_ranges, outside_in};
 use proptest::arbitrary::any;
 use proptest::collection::btree_set;
 use proptest::proptest;
@@ -140,3 +140,112 @@ proptest! {
         assert!(outside_in(values).eq(outside_in(bitmap)));
     }
 }
+
+#[test]
+fn ranges() {
+    let mut treemap = RoaringTreemap::new();
+    treemap.insert_range(10..20);
+    treemap.insert_range(0xFFFF_0000..0x1_0001_0000);
+    treemap.insert_range(0x2_FFFF_FFFF..0x3_0000_0010);
+    treemap.insert(u64::MAX);
+    let expected = naive_ranges(treemap.iter());
+
+    assert_eq!(expected.len(), 4);
+    assert!(treemap.ranges().eq(expected.iter().cloned()));
+    assert!(treemap.ranges().rev().eq(expected.iter().rev().cloned()));
+    assert!(outside_in(treemap.ranges()).eq(outside_in(expected)));
+}
+
+proptest! {
+    #[test]
+    fn ranges_iter(values in btree_set(0xFFFE_0000u64..0x1_0002_0000, ..=50_000)) {
+        let treemap = RoaringTreemap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let expected = naive_ranges(values);
+
+        assert!(treemap.ranges().eq(expected.iter().cloned()));
+        assert!(treemap.ranges().rev().eq(expected.iter().rev().cloned()));
+        assert!(outside_in(treemap.ranges()).eq(outside_in(expected)));
+    }
+}
+
+proptest! {
+    #[test]
+    fn next_many(
+        values in btree_set(0xFFFE_0000u64..0x1_0002_0000, ..=50_000),
+        len in 1usize..5_000,
+        back in 0usize..100,
+    ) {
+        let treemap = RoaringTreemap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let expected: Vec<u64> = values.iter().cloned().collect();
+        let expected = &expected[..expected.len().saturating_sub(back)];
+
+        let mut iter = treemap.iter();
+        iter.by_ref().rev().take(back).for_each(drop);
+        let mut read = Vec::new();
+        let mut buffer = vec![0; len];
+        loop {
+            let n = iter.next_many(&mut buffer);
+            read.extend_from_slice(&buffer[..n]);
+            if n < len {
+                break;
+            }
+        }
+        assert_eq!(read, expected);
+        assert_eq!(iter.len(), 0);
+
+        let mut iter = treemap.into_iter();
+        let mut buffer = vec![0; values.len()];
+        assert_eq!(iter.next_many(&mut buffer), values.len());
+        assert!(buffer.iter().eq(values.iter()));
+        assert_eq!(iter.next(), None);
+    }
+}
+
+proptest! {
+    #[test]
+    fn range_iter(
+        values in btree_set(0xFFFE_0000u64..0x2_0002_0000, ..=10_000),
+        start in 0xFFFE_0000u64..0x2_0002_0000,
+        len in 0u64..0x1_0004_0000,
+    ) {
+        let treemap = RoaringTreemap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let expected: Vec<u64> = values.range(start..start + len).cloned().collect();
+
+        let iter = treemap.range(start..start + len);
+        assert_eq!(iter.len(), expected.len());
+        assert!(iter.eq(expected.iter().cloned()));
+        assert!(treemap.range(start..start + len).rev().eq(expected.iter().rev().cloned()));
+
+        let iter = treemap.into_range(start..start + len);
+        assert_eq!(iter.len(), expected.len());
+        assert!(iter.eq(expected.iter().cloned()));
+    }
+}
+
+proptest! {
+    #[test]
+    fn advance_to_and_advance_back_to(
+        values in btree_set(0xFFFE_0000u64..0x2_0002_0000, ..=10_000),
+        front in 0xFFFE_0000u64..0x2_0002_0000,
+        back in 0xFFFE_0000u64..0x2_0002_0000,
+        consumed in 0usize..10,
+    ) {
+        let treemap = RoaringTreemap::from_sorted_iter(values.iter().cloned()).unwrap();
+        let expected: Vec<u64> =
+            values.iter().cloned().skip(consumed).filter(|&v| v >= front && v <= back).collect();
+
+        let mut iter = treemap.iter();
+        iter.by_ref().take(consumed).for_each(drop);
+        iter.advance_to(front);
+        iter.advance_back_to(back);
+        assert_eq!(iter.len(), expected.len());
+        assert!(iter.eq(expected.iter().cloned()));
+
+        let mut iter = treemap.into_iter();
+        iter.by_ref().take(consumed).for_each(drop);
+        iter.advance_back_to(back);
+        iter.advance_to(front);
+        assert_eq!(iter.len(), expected.len());
+        assert!(iter.rev().eq(expected.iter().rev().cloned()));
+    }
+}
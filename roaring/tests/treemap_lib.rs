@@ -117,3 +117,99 @@ fn to_array() {
         assert!(!bitmap.contains(i));
     }
 }
+
+#[test]
+fn hash_and_partial_cmp() {
+    use std::collections::HashSet;
+
+    let a = (0..5000).chain(1 << 32..(1 << 32) + 10).collect::<RoaringTreemap>();
+    let mut b = a.clone();
+    b.optimize();
+    let c = (0..5000).chain(1 << 33..(1 << 33) + 10).collect::<RoaringTreemap>();
+
+    let set: HashSet<RoaringTreemap> = [a.clone(), b.clone(), c.clone()].into_iter().collect();
+    assert_eq!(set.len(), 2);
+
+    assert_eq!(a.partial_cmp(&b), Some(std::cmp::Ordering::Equal));
+    assert!(a < c);
+    assert!((0..4999).collect::<RoaringTreemap>() < a);
+    assert!(RoaringTreemap::from([1 << 32]) > a);
+}
+
+#[test]
+fn split_off_and_append_treemap() {
+    let values = (0..10).chain((1 << 32) - 5..(1 << 32) + 5).chain(3 << 32..(3 << 32) + 10);
+    let treemap: RoaringTreemap = values.clone().collect();
+
+    for at in [0, 5, 10, (1 << 32) - 1, 1 << 32, (1 << 32) + 5, 2 << 32, (3 << 32) + 1, u64::MAX] {
+        let mut lower = treemap.clone();
+        let mut upper = lower.split_off(at);
+        assert!(lower.iter().eq(values.clone().filter(|&v| v < at)));
+        assert!(upper.iter().eq(values.clone().filter(|&v| v >= at)));
+        assert!(lower.bitmaps().chain(upper.bitmaps()).all(|(_, bitmap)| !bitmap.is_empty()));
+
+        let upper_len = upper.len();
+        assert_eq!(lower.append_treemap(&mut upper), Ok(upper_len));
+        assert_eq!(lower, treemap);
+        assert!(upper.is_empty());
+    }
+
+    let mut other: RoaringTreemap = (5..15).collect();
+    let error = treemap.clone().append_treemap(&mut other).unwrap_err();
+    assert_eq!((error.max(), error.appended_min()), ((3 << 32) + 9, 5));
+    assert_eq!(other.len(), 10);
+}
+
+#[test]
+fn add_offset() {
+    let values = [0, 10, u32::MAX as u64 - 1, 1 << 32, (5 << 32) + 7, u64::MAX - 3];
+    let treemap: RoaringTreemap = values.iter().collect();
+
+    for offset in
+        [0, 1, -1, 7, -11, 1 << 32, -(1 << 32), (3 << 32) + 5, -(3 << 32) - 5, i64::MAX, i64::MIN]
+    {
+        let expected = values.iter().filter_map(|&v| {
+            let shifted = i128::from(v) + i128::from(offset);
+            u64::try_from(shifted).ok()
+        });
+        assert!(treemap.add_offset(offset).iter().eq(expected), "offset {offset}");
+    }
+}
+
+#[test]
+fn contains_range_and_range_cardinality() {
+    let mut treemap = RoaringTreemap::new();
+    treemap.insert_range(10..(2 << 32) + 10);
+    treemap.insert((3 << 32) + 1);
+
+    assert!(treemap.contains_range(10..(2 << 32) + 10));
+    assert!(treemap.contains_range(u32::MAX as u64..=1 << 32));
+    assert!(!treemap.contains_range(9..20));
+    assert!(!treemap.contains_range(10..=(2 << 32) + 10));
+    assert!(!treemap.contains_range((3 << 32)..(3 << 32) + 2));
+    assert!(treemap.contains_range(5..5));
+
+    assert_eq!(treemap.range_cardinality(..), treemap.len());
+    assert_eq!(treemap.range_cardinality(0..20), 10);
+    assert_eq!(treemap.range_cardinality((1 << 32) - 5..(1 << 32) + 5), 10);
+    assert_eq!(treemap.range_cardinality((2 << 32)..=(3 << 32) + 1), 11);
+    assert_eq!(treemap.range_cardinality(5..5), 0);
+}
+
+#[test]
+fn remove_smallest_and_remove_biggest() {
+    let values = [0, 10, u32::MAX as u64, 1 << 32, (1 << 32) + 1, (5 << 32) + 7, u64::MAX];
+    let treemap: RoaringTreemap = values.iter().collect();
+
+    for n in 0..=values.len() as u64 + 1 {
+        let mut smallest_removed = treemap.clone();
+        smallest_removed.remove_smallest(n);
+        assert!(smallest_removed.iter().eq(values.iter().cloned().skip(n as usize)));
+
+        let mut biggest_removed = treemap.clone();
+        biggest_removed.remove_biggest(n);
+        let kept = values.len().saturating_sub(n as usize);
+        assert!(biggest_removed.iter().eq(values.iter().cloned().take(kept)));
+        assert!(biggest_removed.bitmaps().all(|(_, bitmap)| !bitmap.is_empty()));
+    }
+}
@@ -0,0 +1,46 @@
+#![cfg(feature = "std")]
+
+extern crate roaring;
+
+use roaring::{RoaringBitmap, RoaringBitmapView};
+
+// Test data from https://github.com/RoaringBitmap/RoaringFormatSpec/tree/master/testdata
+static BITMAP_WITHOUT_RUNS: &[u8] = include_bytes!("bitmapwithoutruns.bin");
+static BITMAP_WITH_RUNS: &[u8] = include_bytes!("bitmapwithruns.bin");
+
+fn test_data_bitmap() -> RoaringBitmap {
+    (0..100)
+        .map(|i| i * 1000)
+        .chain((100_000..200_000).map(|i| i * 3))
+        .chain(700_000..800_000)
+        .collect::<RoaringBitmap>()
+}
+
+#[test]
+fn view_without_runs_from_provided_data() {
+    let bitmap = test_data_bitmap();
+    let view = RoaringBitmapView::new(BITMAP_WITHOUT_RUNS).unwrap();
+    assert_eq!(view.len(), bitmap.len());
+    assert!(view.iter().eq(bitmap.iter()));
+    assert_eq!(view.to_bitmap(), bitmap);
+}
+
+#[test]
+fn view_with_runs_from_provided_data() {
+    let bitmap = test_data_bitmap();
+    let view = RoaringBitmapView::new(BITMAP_WITH_RUNS).unwrap();
+    assert_eq!(view.len(), bitmap.len());
+    assert!(view.iter().eq(bitmap.iter()));
+    assert!(view.range(750_000..).eq(bitmap.range(750_000..)));
+    assert_eq!(view.rank(750_000), bitmap.rank(750_000));
+    assert_eq!(view.select(40_000), bitmap.select(40_000));
+    assert!(view.contains(799_999));
+    assert!(!view.contains(800_000));
+}
+
+#[test]
+fn view_rejects_unknown_cookie() {
+    let mut bytes = BITMAP_WITH_RUNS.to_vec();
+    bytes[0] = 0;
+    assert!(RoaringBitmapView::new(&bytes).is_err());
+}